@@ -1,25 +1,32 @@
 // a simple sprite shader for 2D
 struct VertexInput {
     @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
 }
 
 struct InstanceInput {
-    @location(1) mm_0: vec4<f32>,
-    @location(2) mm_1: vec4<f32>,
-    @location(3) mm_2: vec4<f32>,
-    @location(4) mm_3: vec4<f32>,
+    @location(2) mm_0: vec4<f32>,
+    @location(3) mm_1: vec4<f32>,
+    @location(4) mm_2: vec4<f32>,
+    @location(5) mm_3: vec4<f32>,
 
-    @location(5) color: vec4<f32>,
+    @location(6) color: vec4<f32>,
 }
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
-    @location(0) color: vec3<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) uv: vec2<f32>,
 }
 
 @group(0) @binding(0)
 var<uniform> camera: mat4x4<f32>;
 
+@group(1) @binding(0)
+var sprite_texture: texture_2d<f32>;
+@group(1) @binding(1)
+var sprite_sampler: sampler;
+
 @vertex
 fn vs_main(
     vertex: VertexInput,
@@ -35,12 +42,14 @@ fn vs_main(
     var out: VertexOutput;
 
     out.clip_position = camera * model_matrix * vec4<f32>(vertex.position, 0.0, 1.0);
-    out.color = instance.color.xyz;
+    out.color = instance.color;
+    out.uv = vertex.uv;
 
     return out;
 }
 
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4<f32>(input.color, 1.0);
+    // untextured sprites sample a plain white texture
+    return textureSample(sprite_texture, sprite_sampler, input.uv) * input.color;
 }
@@ -1,27 +1,70 @@
+use std::path::Path;
+
 use ferret_engine::prelude::{
-    Application, Camera2D, FrameTimer, InputState, Material2D, Mesh2D, Resources, Shape2D,
-    Transform, World,
+    Application, Axis2D, Binding, Camera2D, FrameTimer, GamepadButton, GamepadStick, InputMap,
+    InputState, Material2D, Mesh2D, Name, NameIndex, Resources, Shape2D, Transform, World,
 };
-use glam::{Quat, Vec3, vec3};
-use hecs::With;
+use glam::vec3;
 use winit::keyboard::KeyCode;
 
+/// The config file the sandbox's input bindings are loaded from, if it exists.
+const INPUT_CONFIG: &str = "sandbox_input.json";
+
 fn main() {
     ferret_engine::init_logging();
 
-    Application::builder()
+    let builder = Application::builder()
         .add_startup_system(setup)
         .add_update_system(rotate)
         .add_update_system(handle_player_input)
-        .run();
+        .with_input_map(input_map());
+
+    #[cfg(feature = "inspector")]
+    let builder = builder.with_inspector(KeyCode::F1);
+
+    builder.run();
+}
+
+/// Loads the input bindings from the config file, falling back to the default bindings if it
+/// doesn't exist.
+///
+/// A config file which exists but can't be read or parsed is reported and exits, rather than
+/// silently discarding the user's bindings.
+fn input_map() -> InputMap {
+    if !Path::new(INPUT_CONFIG).exists() {
+        return default_input_map();
+    }
+
+    InputMap::load(INPUT_CONFIG).unwrap_or_else(|e| {
+        log::error!("failed to load input config: {e:#}");
+        std::process::exit(1);
+    })
+}
+
+/// Returns the default input bindings of the sandbox.
+fn default_input_map() -> InputMap {
+    InputMap::new()
+        .with_axis("move", Axis2D::wasd().with_stick(GamepadStick::Left))
+        .with_action(
+            "rotate_left",
+            [
+                Binding::Key(KeyCode::ArrowLeft),
+                Binding::Gamepad(GamepadButton::LeftBumper),
+            ],
+        )
+        .with_action(
+            "rotate_right",
+            [
+                Binding::Key(KeyCode::ArrowRight),
+                Binding::Gamepad(GamepadButton::RightBumper),
+            ],
+        )
 }
 
 struct Rotate {
     speed: f32,
 }
 
-struct Player;
-
 fn setup(world: &mut World, _: &mut Resources) {
     world.spawn((Camera2D { half_width: 10.0 }, Transform::default()));
 
@@ -44,7 +87,7 @@ fn setup(world: &mut World, _: &mut Resources) {
         },
         Mesh2D(Shape2D::Square),
         Transform::with_translation(vec3(-4.0, 0.0, 0.0)),
-        Player,
+        Name::new("player"),
     ));
 }
 
@@ -52,42 +95,35 @@ fn rotate(world: &mut World, resources: &mut Resources) {
     let dt = resources.get::<FrameTimer>().dt();
 
     for (_, (transform, rotation)) in world.query_mut::<(&mut Transform, &Rotate)>() {
-        transform.rotation *= Quat::from_rotation_z(rotation.speed * dt);
+        transform.rotate_2d(rotation.speed * dt);
     }
 }
 
 fn handle_player_input(world: &mut World, resources: &mut Resources) {
     let dt = resources.get::<FrameTimer>().dt();
     let input = resources.get::<InputState>();
+    let input_map = resources.get::<InputMap>();
 
     let translation_speed = 5.0;
     let rotation_speed = 90.0f32.to_radians();
 
-    for (_, transform) in world.query_mut::<With<&mut Transform, &Player>>() {
-        let mut translation = Vec3::ZERO;
-        let mut rotation = Quat::IDENTITY;
-
-        if input.key_held(KeyCode::KeyW) {
-            translation += Vec3::Y;
-        }
-        if input.key_held(KeyCode::KeyS) {
-            translation += Vec3::NEG_Y;
-        }
-        if input.key_held(KeyCode::KeyD) {
-            translation += Vec3::X;
-        }
-        if input.key_held(KeyCode::KeyA) {
-            translation += Vec3::NEG_X;
-        }
-
-        if input.key_pressed(KeyCode::ArrowLeft) {
-            rotation = Quat::from_rotation_z(rotation_speed);
-        }
-        if input.key_pressed(KeyCode::ArrowRight) {
-            rotation = Quat::from_rotation_z(rotation_speed * -1.0);
-        }
-
-        transform.translation += translation * translation_speed * dt;
-        transform.rotation *= rotation;
+    let Some(player) = resources.get::<NameIndex>().get("player") else {
+        return;
+    };
+    let Ok(mut transform) = world.get::<&mut Transform>(player) else {
+        return;
+    };
+
+    let translation = input_map.axis(input, "move").extend(0.0);
+    let mut rotation = 0.0;
+
+    if input_map.pressed(input, "rotate_left") {
+        rotation = rotation_speed;
+    }
+    if input_map.pressed(input, "rotate_right") {
+        rotation = rotation_speed * -1.0;
     }
+
+    transform.translation += translation * translation_speed * dt;
+    transform.rotate_2d(rotation);
 }
@@ -1,4 +1,6 @@
-use std::sync::Arc;
+use std::{borrow::Cow, path::PathBuf, sync::Arc};
+
+use glam::UVec2;
 
 #[cfg(target_family = "wasm")]
 use winit::event_loop::EventLoopProxy;
@@ -6,16 +8,40 @@ use winit::event_loop::EventLoopProxy;
 use winit::{
     application::ApplicationHandler,
     dpi::PhysicalSize,
-    event::WindowEvent,
+    event::{DeviceEvent, DeviceId, WindowEvent},
     event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
     window::{Window, WindowId},
 };
 
+#[cfg(feature = "inspector")]
+use winit::keyboard::KeyCode;
+
+#[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+use crate::hot_reload::{HotReload, HotReloadSettings};
+#[cfg(feature = "gilrs")]
+use crate::input::gamepad::GilrsBackend;
+#[cfg(feature = "inspector")]
+use crate::inspector::{Inspector, InspectorSettings};
 use crate::{
-    ecs::{Resources, Schedule, World},
-    input::InputState,
-    renderer::Renderer,
-    timer::FrameTimer,
+    clock::Clock,
+    diagnostics::FrameDiagnostics,
+    ecs::{Events, Lifecycle, Resources, Schedule, SystemControl, World},
+    input::{
+        InputState,
+        actions::InputMap,
+        buffer::InputBuffer,
+        event::InputEvent,
+        gamepad::GamepadBackend,
+        replay::{ReplaySession, ReplaySettings, ReplayStatus},
+        text::{self, ImeControl, ImeEvent, TextInput},
+    },
+    name::NameIndex,
+    profiler::Profiler,
+    reflect::{Reflect, TypeRegistry},
+    renderer::{EmbeddedTextures, Renderer, camera::Viewport},
+    scene::MapEntities,
+    spatial::SpatialIndex,
+    timer::{self, DelayedCommands, FrameTimer},
 };
 
 pub struct Application {
@@ -31,6 +57,19 @@ pub struct Application {
 
     /// The renderer responsible for rendering the scene and UI.
     renderer: Renderer,
+
+    /// The input recording or replay in progress, if enabled.
+    replay: Option<ReplaySession>,
+
+    /// The entity inspector overlay, if enabled.
+    #[cfg(feature = "inspector")]
+    inspector: Option<Inspector>,
+
+    /// The watcher of the hot reloadable gameplay library, if enabled.
+    ///
+    /// Must be dropped last, as the world, resources and schedule may point into the library.
+    #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+    hot_reload: Option<HotReload>,
 }
 
 impl Application {
@@ -42,15 +81,42 @@ impl Application {
     /// Creates a new [`Application`].
     pub(crate) async fn new(
         window: Arc<Window>,
-        schedule: Schedule,
+        mut schedule: Schedule,
         mut resources: Resources,
     ) -> Self {
-        let renderer = Renderer::new(Arc::clone(&window)).await.unwrap();
+        let mut renderer = Renderer::new(Arc::clone(&window)).await.unwrap();
+
+        if let Some(embedded) = resources.remove::<EmbeddedTextures>() {
+            renderer.embed_textures(embedded);
+        }
+
+        let replay = resources.try_get::<ReplaySettings>().and_then(|settings| {
+            ReplaySession::new(settings, renderer.size(), window.scale_factor())
+                .inspect_err(|e| log::error!("failed to start replay: {e:#}"))
+                .ok()
+        });
+
+        // replays drive the frame timer with the recorded frame durations instead
+        if let Some(clock) = replay.as_ref().and_then(ReplaySession::clock) {
+            resources.insert(FrameTimer::with_clock(clock.clone()));
+        }
+
+        let world = Self::prepare(
+            &mut schedule,
+            &mut resources,
+            renderer.size(),
+            window.scale_factor(),
+        );
 
-        let world = World::new();
+        #[cfg(feature = "inspector")]
+        let inspector = resources
+            .try_get::<InspectorSettings>()
+            .map(|settings| Inspector::new(&window, *settings));
 
-        resources.insert(FrameTimer::default());
-        resources.insert(InputState::default());
+        #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+        let hot_reload = resources
+            .try_get::<HotReloadSettings>()
+            .map(|settings| HotReload::new(settings.clone()));
 
         Self {
             window,
@@ -58,15 +124,86 @@ impl Application {
             world,
             resources,
             schedule,
+            replay,
+            #[cfg(feature = "inspector")]
+            inspector,
+            #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+            hot_reload,
         }
     }
 
+    /// Inserts all engine resources and systems which don't depend on a window, returning the
+    /// world to run them on.
+    fn prepare(
+        schedule: &mut Schedule,
+        resources: &mut Resources,
+        size: PhysicalSize<u32>,
+        scale_factor: f64,
+    ) -> World {
+        resources.insert(Viewport::new(size.width, size.height));
+
+        resources.insert(ReplayStatus::default());
+
+        if resources.try_get::<FrameTimer>().is_none() {
+            resources.insert(FrameTimer::default());
+        }
+
+        if resources.try_get::<SpatialIndex>().is_none() {
+            resources.insert(SpatialIndex::default());
+        }
+
+        resources.insert(DelayedCommands::default());
+
+        let mut input = InputState::default();
+        input.set_scale_factor(scale_factor);
+
+        if let Some(backend) = Self::gamepad_backend(resources) {
+            input.set_gamepad_backend(backend);
+        }
+
+        resources.insert(input);
+        resources.insert(Events::<TextInput>::default());
+        resources.insert(Events::<ImeEvent>::default());
+        resources.insert(ImeControl::default());
+        resources.insert(NameIndex::default());
+        resources.insert(SystemControl::default());
+
+        schedule.add_event::<TextInput>();
+        schedule.add_event::<ImeEvent>();
+        schedule.add_pre_update_system(timer::tick_timers);
+        schedule.add_pre_update_system(InputBuffer::update);
+        schedule.add_pre_update_system(SpatialIndex::sync);
+        NameIndex::observe(schedule);
+
+        // the world only records lifecycle events for the observers registered by now
+        let mut world = World::new();
+        schedule.watch(&mut world);
+
+        world
+    }
+
     /// Handles an incoming [`WindowEvent`]
     fn window_event(&mut self, event_loop: &ActiveEventLoop, event: WindowEvent) {
-        self.resources.get_mut::<InputState>().window_event(&event);
+        #[cfg(feature = "inspector")]
+        let consumed = self
+            .inspector
+            .as_mut()
+            .is_some_and(|inspector| inspector.window_event(&self.window, &event));
+        #[cfg(not(feature = "inspector"))]
+        let consumed = false;
+
+        if !consumed && let Some(event) = InputEvent::from_window_event(&event) {
+            self.input_event(event);
+        }
 
         match event {
-            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::CloseRequested => {
+                if let Some(replay) = &mut self.replay {
+                    replay.save();
+                }
+
+                event_loop.exit();
+            }
 
             WindowEvent::Resized(size) => self.resize(size),
 
@@ -81,26 +218,210 @@ impl Application {
 
     /// Runs the main update cycle of the application.
     fn update(&mut self) {
-        self.resources.get_mut::<FrameTimer>().tick();
+        self.resources.get_mut::<Profiler>().begin_frame();
+
+        if let Some(replay) = &mut self.replay {
+            let events = replay.begin_frame(self.resources.get_mut::<ReplayStatus>());
+
+            for event in events {
+                self.apply_input_event(event);
+            }
+        }
+
+        advance_time(&mut self.resources);
+
+        for event in self.resources.get_mut::<InputState>().poll_gamepads() {
+            self.input_event(InputEvent::Gamepad(event));
+        }
+
+        #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+        if let Some(hot_reload) = &mut self.hot_reload {
+            hot_reload.poll(&mut self.schedule, &mut self.resources);
+        }
+
         self.schedule.execute(&mut self.world, &mut self.resources);
+
+        if let Some(replay) = &mut self.replay {
+            let delta = self.resources.get::<FrameTimer>().real_delta();
+            let before = *self.resources.get::<ReplayStatus>();
+            let mut status = before;
+            replay.end_frame(delta, &self.world, &self.resources, &mut status);
+
+            if std::mem::take(&mut status.save_requested) {
+                replay.save();
+            }
+
+            // the replay drove the viewport and scale factor, so match the real window again
+            if !replay.accepts_input(&before) && replay.accepts_input(&status) {
+                let size = self.window.inner_size();
+                apply_input_event(
+                    &mut self.resources,
+                    &InputEvent::Resized(UVec2::new(size.width, size.height)),
+                );
+                apply_input_event(
+                    &mut self.resources,
+                    &InputEvent::ScaleFactorChanged(self.window.scale_factor()),
+                );
+            }
+
+            *self.resources.get_mut::<ReplayStatus>() = status;
+        }
+
         self.resources.get_mut::<InputState>().flush();
+        self.resources.get_mut::<ImeControl>().apply(&self.window);
     }
 
     /// Renders the game world and all UI.
     fn render(&mut self) {
         self.window.pre_present_notify();
-        self.renderer.render(&mut self.world);
+
+        #[cfg(feature = "inspector")]
+        if let Some(inspector) = &mut self.inspector
+            && let Some(frame) = inspector.run(&self.window, &mut self.world, &self.resources)
+        {
+            self.renderer.submit_overlay(frame);
+        }
+
+        let profiler = self.resources.get_mut::<Profiler>();
+        self.renderer.render(&mut self.world, profiler);
+        profiler.end_frame();
 
         self.window.request_redraw();
     }
 
+    /// Handles an incoming real [`InputEvent`], recording it or ignoring it while a replay is
+    /// being played back.
+    fn input_event(&mut self, event: InputEvent) {
+        if let Some(replay) = &mut self.replay {
+            if !replay.accepts_input(self.resources.get::<ReplayStatus>()) {
+                return;
+            }
+
+            replay.record(&event);
+        }
+
+        self.apply_input_event(event);
+    }
+
+    /// Applies an [`InputEvent`] to the input state and sends any resulting text events.
+    fn apply_input_event(&mut self, event: InputEvent) {
+        apply_input_event(&mut self.resources, &event);
+    }
+
+    /// Takes the gamepad backend added through the builder, falling back to physical gamepads if
+    /// enabled.
+    fn gamepad_backend(resources: &mut Resources) -> Option<Box<dyn GamepadBackend>> {
+        if let Some(backend) = resources.remove::<Box<dyn GamepadBackend>>() {
+            return Some(backend);
+        }
+
+        #[cfg(feature = "gilrs")]
+        match GilrsBackend::new() {
+            Ok(backend) => return Some(Box::new(backend)),
+            Err(e) => log::error!("failed to initialize gamepads: {e:#}"),
+        }
+
+        None
+    }
+
     /// Resizes the internal state of the application to match the window's size.
+    ///
+    /// The viewport is resized through an [`InputEvent`], so that it is recorded and replayed.
     fn resize(&mut self, size: PhysicalSize<u32>) {
         log::debug!("resizing to new size: {size:?}");
         self.renderer.resize(size);
+
+        self.input_event(InputEvent::Resized(UVec2::new(size.width, size.height)));
     }
 }
 
+/// An [`Application`] without a window or renderer, advanced one frame at a time by hand.
+///
+/// Runs the same systems on the same resources as a windowed application, which makes it suitable
+/// for testing gameplay systems by injecting input. Replays, hot reloading and the inspector are
+/// not available.
+pub struct Headless {
+    /// The core ECS game world in which all entities live in.
+    world: World,
+    /// All resources bound to the world.
+    resources: Resources,
+    /// The schedule for all systems to run.
+    schedule: Schedule,
+}
+
+impl Headless {
+    /// The size of the viewport a [`Headless`] application starts with, in physical pixels.
+    pub const DEFAULT_SIZE: (u32, u32) = (1920, 1080);
+
+    /// Runs a single frame: all pre-update systems, all update systems, then flushes the input
+    /// state for the next frame.
+    pub fn update(&mut self) {
+        self.resources.get_mut::<Profiler>().begin_frame();
+
+        advance_time(&mut self.resources);
+
+        for event in self.resources.get_mut::<InputState>().poll_gamepads() {
+            apply_input_event(&mut self.resources, &InputEvent::Gamepad(event));
+        }
+
+        self.schedule.execute(&mut self.world, &mut self.resources);
+
+        self.resources.get_mut::<InputState>().flush();
+        self.resources.get_mut::<Profiler>().end_frame();
+    }
+
+    /// Applies an [`InputEvent`] as if it came from the window, visible to the next frame.
+    pub fn inject(&mut self, event: InputEvent) {
+        apply_input_event(&mut self.resources, &event);
+    }
+
+    /// Changes the size of the viewport in physical pixels.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.inject(InputEvent::Resized(UVec2::new(width, height)));
+    }
+
+    /// Returns the world in which all entities live in.
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Returns the world in which all entities live in, mutably.
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    /// Returns all resources bound to the world.
+    pub fn resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    /// Returns all resources bound to the world, mutably.
+    pub fn resources_mut(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+}
+
+/// Ticks the frame timer and all other time based state at the start of a frame.
+fn advance_time(resources: &mut Resources) {
+    resources.get_mut::<FrameTimer>().tick();
+
+    let frame = resources.get::<FrameTimer>().frame();
+    resources.get_mut::<FrameDiagnostics>().tick(frame);
+
+    let time = resources.get::<FrameTimer>().real_elapsed();
+    resources.get_mut::<InputState>().update_touches(time);
+}
+
+/// Applies an [`InputEvent`] to the input state and viewport, and sends any resulting text events.
+fn apply_input_event(resources: &mut Resources, event: &InputEvent) {
+    if let InputEvent::Resized(size) = event {
+        *resources.get_mut::<Viewport>() = Viewport::new(size.x, size.y);
+    }
+
+    resources.get_mut::<InputState>().inject(event);
+    text::input_event(resources, event);
+}
+
 enum ApplicationRunner {
     Initializing {
         /// The systems to be excecuted.
@@ -176,6 +497,16 @@ impl ApplicationHandler<Application> for ApplicationRunner {
         app.window_event(event_loop, event);
     }
 
+    fn device_event(&mut self, _: &ActiveEventLoop, _: DeviceId, event: DeviceEvent) {
+        let Self::Running(app) = self else {
+            return;
+        };
+
+        if let Some(event) = InputEvent::from_device_event(&event) {
+            app.input_event(event);
+        }
+    }
+
     fn user_event(&mut self, _: &ActiveEventLoop, mut app: Application) {
         app.resize(app.window.inner_size());
         app.window.request_redraw();
@@ -212,6 +543,206 @@ impl ApplicationBuilder {
         self
     }
 
+    /// Registers a system to be run in the update cycle of the app, which can be enabled,
+    /// disabled or removed at runtime by its label through [`SystemControl`].
+    pub fn add_labeled_update_system<T: Fn(&mut World, &mut Resources) + 'static>(
+        mut self,
+        label: impl Into<Cow<'static, str>>,
+        system: T,
+    ) -> Self {
+        self.schedule.add_labeled_update_system(label, system);
+        self
+    }
+
+    /// Registers a system to be run only when triggered by its label through
+    /// [`SystemControl::run`].
+    pub fn add_one_shot_system<T: Fn(&mut World, &mut Resources) + 'static>(
+        mut self,
+        label: impl Into<Cow<'static, str>>,
+        system: T,
+    ) -> Self {
+        self.schedule.add_one_shot_system(label, system);
+        self
+    }
+
+    /// Registers an [`Events`] queue of type `T`, cleared at the end of every frame.
+    ///
+    /// Systems reading events sent by another system have to be added after it.
+    pub fn add_event<T: 'static>(mut self) -> Self {
+        self.resources.insert(Events::<T>::default());
+        self.schedule.add_event::<T>();
+        self
+    }
+
+    /// Registers an observer to be run whenever a component of type `T` is added or removed,
+    /// depending on the given [`Lifecycle`] event.
+    pub fn add_observer<T, F>(mut self, lifecycle: Lifecycle, observer: F) -> Self
+    where
+        T: hecs::Component,
+        F: Fn(&mut World, &mut Resources, hecs::Entity) + 'static,
+    {
+        self.schedule.add_observer::<T, F>(lifecycle, observer);
+        self
+    }
+
+    /// Registers an observer to be run whenever an entity is despawned.
+    pub fn add_despawn_observer<F>(mut self, observer: F) -> Self
+    where
+        F: Fn(&mut World, &mut Resources, hecs::Entity) + 'static,
+    {
+        self.schedule.add_despawn_observer(observer);
+        self
+    }
+
+    /// Registers a reflected component type, making its fields accessible by name at runtime and
+    /// saving it to and loading it from scenes under its type name.
+    pub fn register_type<T: Reflect>(mut self) -> Self {
+        self.resources.get_mut::<TypeRegistry>().register::<T>();
+        self
+    }
+
+    /// Registers a reflected component type holding entity references, like
+    /// [`register_type`](Self::register_type), remapping the references when spawning scenes.
+    pub fn register_mapped_type<T: Reflect + MapEntities>(mut self) -> Self {
+        self.resources
+            .get_mut::<TypeRegistry>()
+            .register_mapped::<T>();
+        self
+    }
+
+    /// Drives the [`FrameTimer`] with the given clock instead of the system clock, e.g. a
+    /// [`FixedClock`](crate::prelude::FixedClock) for deterministic simulations.
+    pub fn with_clock(mut self, clock: impl Clock) -> Self {
+        self.resources.insert(FrameTimer::with_clock(clock));
+        self
+    }
+
+    /// Indexes the bounding boxes of all renderable entities in a grid with the given cell size in
+    /// world units, which should be about the size of a typical entity.
+    pub fn with_spatial_index(mut self, cell_size: f32) -> Self {
+        self.resources.insert(SpatialIndex::new(cell_size));
+        self
+    }
+
+    /// Records a Chrome/Perfetto trace of the first `frames` frames, written as JSON to the given
+    /// path once complete.
+    pub fn with_trace(mut self, path: impl Into<PathBuf>, frames: u32) -> Self {
+        self.resources
+            .get_mut::<Profiler>()
+            .record_trace(path, frames);
+        self
+    }
+
+    /// Uses the given encoded image in place of the texture file at `path`, e.g. from
+    /// `include_bytes!`, which is the only way to load textures on the web.
+    pub fn with_embedded_texture(
+        mut self,
+        path: impl Into<Cow<'static, str>>,
+        bytes: &'static [u8],
+    ) -> Self {
+        if self.resources.try_get::<EmbeddedTextures>().is_none() {
+            self.resources.insert(EmbeddedTextures::default());
+        }
+
+        self.resources
+            .get_mut::<EmbeddedTextures>()
+            .0
+            .insert(path.into(), bytes);
+        self
+    }
+
+    /// Sets the bindings of all named input actions and axes.
+    pub fn with_input_map(mut self, input_map: InputMap) -> Self {
+        self.resources.insert(input_map);
+        self
+    }
+
+    /// Sets how long input presses are remembered for and which axis' directions are buffered.
+    pub fn with_input_buffer(mut self, buffer: InputBuffer) -> Self {
+        self.resources.insert(buffer);
+        self
+    }
+
+    /// Reads gamepad input from the given backend, such as a
+    /// [`VirtualGamepad`](crate::prelude::VirtualGamepad) in tests.
+    pub fn with_gamepad_backend(mut self, backend: impl GamepadBackend) -> Self {
+        self.resources
+            .insert::<Box<dyn GamepadBackend>>(Box::new(backend));
+        self
+    }
+
+    /// Records all input, window size changes and frame durations into a replay file at the given
+    /// path, optionally along with a checksum of the world every frame.
+    ///
+    /// The file is written when the window is closed, or earlier through
+    /// [`ReplayStatus::save_requested`].
+    pub fn with_input_recording(mut self, path: impl Into<PathBuf>, checksums: bool) -> Self {
+        self.resources.insert(ReplaySettings::Record {
+            path: path.into(),
+            checksums,
+        });
+        self
+    }
+
+    /// Plays back the replay file at the given path, reporting its progress and any desyncs in
+    /// [`ReplayStatus`].
+    pub fn with_input_replay(mut self, path: impl Into<PathBuf>) -> Self {
+        self.resources
+            .insert(ReplaySettings::Replay { path: path.into() });
+        self
+    }
+
+    /// Streams the real time every frame takes as CSV to the given path.
+    pub fn with_frame_time_csv(mut self, path: impl Into<PathBuf>) -> Self {
+        self.resources
+            .get_mut::<FrameDiagnostics>()
+            .record_csv(path);
+        self
+    }
+
+    /// Loads gameplay systems from the dynamic library at the given path, reloading them whenever
+    /// the library is rebuilt while the world and resources are preserved.
+    ///
+    /// The library must export a [`RegisterFn`](crate::prelude::RegisterFn) named
+    /// [`REGISTER_SYMBOL`](crate::prelude::REGISTER_SYMBOL).
+    #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+    pub fn with_hot_reload(mut self, path: impl Into<PathBuf>) -> Self {
+        self.resources
+            .insert(HotReloadSettings { path: path.into() });
+        self
+    }
+
+    /// Enables the entity inspector overlay, toggled by pressing the given key.
+    #[cfg(feature = "inspector")]
+    pub fn with_inspector(mut self, toggle_key: KeyCode) -> Self {
+        self.resources.insert(InspectorSettings { toggle_key });
+        self
+    }
+
+    /// Builds a [`Headless`] application instead of opening a window, for driving frames by hand.
+    ///
+    /// Startup systems run as part of the first [`Headless::update`].
+    pub fn build_headless(self) -> Headless {
+        let Self {
+            mut schedule,
+            mut resources,
+        } = self;
+
+        let (width, height) = Headless::DEFAULT_SIZE;
+        let world = Application::prepare(
+            &mut schedule,
+            &mut resources,
+            PhysicalSize::new(width, height),
+            1.0,
+        );
+
+        Headless {
+            world,
+            resources,
+            schedule,
+        }
+    }
+
     /// Finalizes the [`Application`] and runs it.
     pub fn run(self) {
         let event_loop = EventLoop::<Application>::with_user_event().build().unwrap();
@@ -233,9 +764,16 @@ impl ApplicationBuilder {
 
 impl Default for ApplicationBuilder {
     fn default() -> Self {
+        let mut resources = Resources::new();
+        resources.insert(TypeRegistry::default());
+        resources.insert(Profiler::default());
+        resources.insert(FrameDiagnostics::default());
+        resources.insert(InputMap::default());
+        resources.insert(InputBuffer::default());
+
         Self {
             schedule: Schedule::new(),
-            resources: Resources::new(),
+            resources,
         }
     }
 }
@@ -0,0 +1,106 @@
+use std::{cell::Cell, rc::Rc};
+
+use web_time::{Duration, Instant};
+
+/// A source of time for the [`FrameTimer`](crate::timer::FrameTimer), queried once per frame.
+pub trait Clock: 'static {
+    /// Returns the current time, measured from an arbitrary but fixed starting point.
+    fn now(&mut self) -> Duration;
+}
+
+/// A [`Clock`] reading the system's monotonic clock, used by default.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    /// The instant the clock was created, which all times are measured from.
+    start: Instant,
+}
+
+/// A [`Clock`] advancing by a fixed step every time it is read, making every frame last exactly
+/// the same amount of time regardless of how long it actually took.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock {
+    /// The current time of the clock.
+    now: Duration,
+    /// How far the clock advances every time it is read.
+    step: Duration,
+}
+
+/// A [`Clock`] which only advances when told to.
+///
+/// Clones share the same time, so a copy kept outside of the application can drive the clock.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    /// The current time of the clock, shared between all clones.
+    now: Rc<Cell<Duration>>,
+}
+
+impl SystemClock {
+    /// Creates a new [`SystemClock`] starting at the current instant.
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&mut self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FixedClock {
+    /// Creates a new [`FixedClock`] advancing by the given step every frame.
+    pub fn new(step: Duration) -> Self {
+        Self {
+            now: Duration::ZERO,
+            step,
+        }
+    }
+
+    /// Creates a new [`FixedClock`] advancing at the given number of frames per second.
+    pub fn from_fps(fps: f64) -> Self {
+        Self::new(Duration::from_secs_f64(1.0 / fps))
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&mut self) -> Duration {
+        self.now += self.step;
+        self.now
+    }
+}
+
+impl ManualClock {
+    /// Creates a new [`ManualClock`] starting at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances the clock by the given duration.
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+
+    /// Sets the current time of the clock.
+    pub fn set(&self, now: Duration) {
+        self.now.set(now);
+    }
+
+    /// Returns the current time of the clock.
+    pub fn get(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&mut self) -> Duration {
+        self.now.get()
+    }
+}
@@ -0,0 +1,237 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use anyhow::Context;
+use web_time::Instant;
+
+/// Tracks a rolling history of frame times to surface hitches that averages hide.
+pub struct FrameDiagnostics {
+    /// The wall clock time each of the most recent frames took in seconds, oldest first.
+    history: VecDeque<f32>,
+    /// The maximum number of frames kept in the history.
+    capacity: usize,
+    /// The wall clock time the last frame started at, independent of the application's clock.
+    last_tick: Option<Instant>,
+
+    /// The CSV file every frame time is written to, if enabled.
+    csv: Option<CsvExport>,
+}
+
+/// A single bucket of a frame time histogram.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistogramBucket {
+    /// The smallest frame time in seconds included in the bucket.
+    pub start: f32,
+    /// The largest frame time in seconds included in the bucket.
+    pub end: f32,
+    /// The number of frames which fell into the bucket.
+    pub count: usize,
+}
+
+/// A CSV file frame times are streamed to.
+struct CsvExport {
+    /// The path of the file, used for error messages.
+    path: PathBuf,
+    /// The buffered writer into the file, if it could be opened.
+    writer: Option<BufWriter<File>>,
+}
+
+impl FrameDiagnostics {
+    /// The number of frames kept in the history by default.
+    pub const DEFAULT_CAPACITY: usize = 1000;
+
+    /// Creates a new [`FrameDiagnostics`] keeping the given number of frames of history.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            history: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            last_tick: None,
+            csv: None,
+        }
+    }
+
+    /// Returns the frame times in seconds of the most recent frames, oldest first.
+    pub fn history(&self) -> impl ExactSizeIterator<Item = f32> + '_ {
+        self.history.iter().copied()
+    }
+
+    /// Returns the maximum number of frames kept in the history.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Sets the maximum number of frames kept in the history, dropping the oldest frames if
+    /// necessary.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+
+        while self.history.len() > self.capacity {
+            self.history.pop_front();
+        }
+    }
+
+    /// Returns the time the most recent frame took in seconds.
+    pub fn last(&self) -> Option<f32> {
+        self.history.back().copied()
+    }
+
+    /// Returns the shortest frame time in the history in seconds.
+    pub fn min(&self) -> Option<f32> {
+        self.history().reduce(f32::min)
+    }
+
+    /// Returns the average frame time in the history in seconds.
+    pub fn avg(&self) -> Option<f32> {
+        (!self.history.is_empty()).then(|| self.history().sum::<f32>() / self.history.len() as f32)
+    }
+
+    /// Returns the longest frame time in the history in seconds.
+    pub fn max(&self) -> Option<f32> {
+        self.history().reduce(f32::max)
+    }
+
+    /// Returns the average frame time in seconds of the slowest given fraction of frames in the
+    /// history, e.g. `0.01` for the 1% low.
+    pub fn low(&self, fraction: f32) -> Option<f32> {
+        if self.history.is_empty() {
+            return None;
+        }
+
+        let mut sorted = self.history().collect::<Vec<_>>();
+        sorted.sort_by(|a, b| b.total_cmp(a));
+
+        let count = ((sorted.len() as f32 * fraction).ceil() as usize).clamp(1, sorted.len());
+        Some(sorted[..count].iter().sum::<f32>() / count as f32)
+    }
+
+    /// Returns the average frame time in seconds of the slowest 1% of frames.
+    pub fn one_percent_low(&self) -> Option<f32> {
+        self.low(0.01)
+    }
+
+    /// Returns the average frame time in seconds of the slowest 0.1% of frames.
+    pub fn point_one_percent_low(&self) -> Option<f32> {
+        self.low(0.001)
+    }
+
+    /// Sorts the frame times in the history into the given number of equally sized buckets,
+    /// spanning from the shortest to the longest frame time.
+    pub fn histogram(&self, buckets: usize) -> Vec<HistogramBucket> {
+        let (Some(min), Some(max)) = (self.min(), self.max()) else {
+            return Vec::new();
+        };
+
+        let buckets = buckets.max(1);
+        let width = (max - min).max(f32::EPSILON) / buckets as f32;
+
+        let mut histogram = (0..buckets)
+            .map(|i| HistogramBucket {
+                start: min + width * i as f32,
+                end: min + width * (i + 1) as f32,
+                count: 0,
+            })
+            .collect::<Vec<_>>();
+
+        for frame_time in self.history() {
+            let index = (((frame_time - min) / width) as usize).min(buckets - 1);
+            histogram[index].count += 1;
+        }
+
+        histogram
+    }
+
+    /// Starts streaming every frame time as a line of CSV to the given path, overwriting it.
+    pub fn record_csv(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+
+        let writer = File::create(&path)
+            .and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                writeln!(writer, "frame,frame_time_ms")?;
+                Ok(writer)
+            })
+            .with_context(|| format!("while creating {}", path.display()))
+            .inspect_err(|e| log::error!("failed to start frame time export: {e:#}"))
+            .ok();
+
+        self.csv = Some(CsvExport { path, writer });
+    }
+
+    /// Stops streaming frame times to CSV, flushing any buffered lines.
+    pub fn stop_csv(&mut self) {
+        self.csv = None;
+    }
+
+    /// Returns if frame times are currently streamed to CSV, which stops if the file can't be
+    /// written.
+    pub fn is_recording_csv(&self) -> bool {
+        self.csv.as_ref().is_some_and(|csv| csv.writer.is_some())
+    }
+
+    /// Marks the start of the given frame, recording the wall clock time the previous frame took.
+    ///
+    /// This is measured directly rather than through the application's clock, so frame times
+    /// stay accurate while the clock is paused, scaled or replaced.
+    pub(crate) fn tick(&mut self, frame: u64) {
+        let now = Instant::now();
+
+        if let Some(last_tick) = self.last_tick.replace(now) {
+            self.push(frame.saturating_sub(1), (now - last_tick).as_secs_f32());
+        }
+    }
+
+    /// Records the time the given frame took in seconds.
+    fn push(&mut self, frame: u64, frame_time: f32) {
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+
+        self.history.push_back(frame_time);
+
+        if let Some(csv) = &mut self.csv {
+            csv.write(frame, frame_time);
+        }
+    }
+}
+
+impl CsvExport {
+    /// Writes a single frame time to the file, disabling the export if it fails.
+    fn write(&mut self, frame: u64, frame_time: f32) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+
+        if let Err(e) = writeln!(writer, "{frame},{}", frame_time * 1000.0) {
+            log::error!("failed to write frame time to {}: {e}", self.path.display());
+            self.writer = None;
+        }
+    }
+
+    /// Flushes all buffered lines to the file.
+    fn flush(&mut self) {
+        if let Some(writer) = &mut self.writer
+            && let Err(e) = writer.flush()
+        {
+            log::error!(
+                "failed to write frame times to {}: {e}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+impl Drop for CsvExport {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+impl Default for FrameDiagnostics {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
@@ -1,22 +1,146 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    any::{TypeId, type_name},
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    ops::{Deref, DerefMut},
+};
 
 use anymap::AnyMap;
+use hecs::{Bundle, Component, ComponentError, DynamicBundle, Entity, NoSuchEntity};
+use web_time::Instant;
+
+use crate::profiler::Profiler;
 
 /// A system is a function that acts on an world, modifying and querying as needed.
 pub type System = Box<dyn Fn(&mut World, &mut Resources) + 'static>;
 
+/// An observer is a function that is run in response to a [`Trigger`] on an entity.
+pub type Observer = Box<dyn Fn(&mut World, &mut Resources, Entity) + 'static>;
+
 /// A world in which entities along with their associated components live in.
-pub struct World(hecs::World);
+///
+/// Spawning, inserting, removing and despawning through this type records the lifecycle events
+/// of observed components; doing so through [`hecs::World`] directly bypasses all observers.
+pub struct World {
+    /// The underlying hecs world.
+    inner: hecs::World,
+    /// The component types which have observers registered for them.
+    observed: Vec<ObservedComponent>,
+    /// The lifecycle events recorded since observers were last run.
+    triggers: Vec<(Trigger, Entity)>,
+    /// Whether every despawned entity is staged, rather than only those with observed components,
+    /// as despawn observers are registered.
+    stage_despawned: bool,
+    /// The components of staged entities despawned since observers were last run.
+    despawned: hecs::World,
+    /// Maps each despawned entity to the entity holding its components in `despawned`.
+    despawned_entities: HashMap<Entity, Entity>,
+}
+
+/// The lifecycle event which causes an observer to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    /// A component of the given type was added to an entity which didn't have one.
+    Add(TypeId),
+    /// A component of the given type was removed from an entity, or its entity was despawned.
+    Remove(TypeId),
+    /// An entity was despawned.
+    Despawn,
+}
+
+/// The kind of component lifecycle event an observer responds to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lifecycle {
+    /// The component was added to an entity which didn't have one.
+    Add,
+    /// The component was removed from an entity, or its entity was despawned.
+    Remove,
+}
+
+/// An update system along with its runtime state.
+struct ScheduledSystem {
+    /// The type name of the system, used for profiling unlabelled systems.
+    name: Cow<'static, str>,
+    /// The label used to refer to the system at runtime, if any.
+    label: Option<Cow<'static, str>>,
+    /// Whether the system currently runs.
+    enabled: bool,
+    /// Whether the system was registered by a hot reloadable library.
+    #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+    reloadable: bool,
+    /// The system itself.
+    system: System,
+}
+
+/// Controls the labelled systems of the schedule at runtime.
+///
+/// All requests are applied at the end of the current update cycle.
+#[derive(Debug, Default)]
+pub struct SystemControl {
+    /// The requests queued since the end of the last update cycle.
+    commands: Vec<SystemCommand>,
+    /// The labels of all disabled systems, as of the end of the last update cycle.
+    disabled: HashSet<Cow<'static, str>>,
+}
+
+/// A request to change the state of labelled systems.
+#[derive(Debug)]
+enum SystemCommand {
+    /// Enables all systems with the label.
+    Enable(Cow<'static, str>),
+    /// Disables all systems with the label.
+    Disable(Cow<'static, str>),
+    /// Removes all systems with the label.
+    Remove(Cow<'static, str>),
+    /// Runs the one-shot system with the label.
+    Run(Cow<'static, str>),
+}
+
+/// A component type which has observers registered for it.
+#[derive(Clone, Copy)]
+struct ObservedComponent {
+    /// The [`TypeId`] of the component.
+    type_id: TypeId,
+    /// Returns if an entity has this component.
+    has: fn(&hecs::World, Entity) -> bool,
+}
 
 /// The resources bound to a world.
 pub struct Resources(AnyMap);
 
 /// Manages the sequential order of running systems that act on a world.
 pub struct Schedule {
-    /// The systems to run once during application initialization.
-    startup_systems: Vec<System>,
+    /// The systems to run once during application initialization, along with their type names.
+    startup_systems: Vec<(Cow<'static, str>, System)>,
+    /// The engine systems to run before the update systems in the application's update cycle,
+    /// along with their type names.
+    pre_update_systems: Vec<(&'static str, System)>,
     /// The systems to run during the application's update cycle.
-    update_systems: Vec<System>,
+    update_systems: Vec<ScheduledSystem>,
+    /// The systems to run once at the end of the update cycle whenever triggered, keyed by label.
+    one_shot_systems: HashMap<Cow<'static, str>, System>,
+    /// The labels of all one-shot systems registered by a hot reloadable library.
+    #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+    reloadable_one_shot_systems: HashSet<Cow<'static, str>>,
+
+    /// The observers to run after each system, along with the trigger they respond to.
+    observers: Vec<(Trigger, Observer)>,
+    /// The component types which have observers registered for them.
+    observed: Vec<ObservedComponent>,
+
+    /// Clears each registered [`Events`] resource at the end of the update cycle.
+    event_clearers: Vec<fn(&mut Resources)>,
+}
+
+/// A queue of events of type `T`, cleared at the end of every update cycle.
+///
+/// Events sent by the engine, such as window input, are sent before any systems run, so every
+/// system sees them exactly once, e.g. a console reading [`TextInput`](crate::prelude::TextInput)
+/// can run anywhere in the schedule. Events sent by a system are only seen by the systems running
+/// after it in the same frame, as they are cleared before any earlier system runs again.
+pub struct Events<T> {
+    /// The events sent during the current frame, in the order they were sent.
+    events: Vec<T>,
 }
 
 impl Schedule {
@@ -24,36 +148,491 @@ impl Schedule {
     pub(crate) fn new() -> Self {
         Self {
             startup_systems: Vec::new(),
+            pre_update_systems: Vec::new(),
             update_systems: Vec::new(),
+            one_shot_systems: HashMap::new(),
+            #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+            reloadable_one_shot_systems: HashSet::new(),
+            observers: Vec::new(),
+            observed: Vec::new(),
+            event_clearers: Vec::new(),
         }
     }
 
+    /// Clears the [`Events`] resource of type `T` at the end of every update cycle.
+    pub(crate) fn add_event<T: 'static>(&mut self) {
+        self.event_clearers.push(|resources| {
+            if let Some(events) = resources.try_get_mut::<Events<T>>() {
+                events.clear();
+            }
+        });
+    }
+
     /// Adds a startup system to the schedule.
     pub fn add_startup_system<F: Fn(&mut World, &mut Resources) + 'static>(&mut self, f: F) {
-        self.startup_systems.push(Box::new(f));
+        self.startup_systems
+            .push((Cow::Borrowed(type_name::<F>()), Box::new(f)));
+    }
+
+    /// Adds an engine system to run before all update systems.
+    pub(crate) fn add_pre_update_system<F: Fn(&mut World, &mut Resources) + 'static>(
+        &mut self,
+        f: F,
+    ) {
+        self.pre_update_systems
+            .push((type_name::<F>(), Box::new(f)));
     }
 
     /// Adds an update system to the schedule.
     pub fn add_update_system<F: Fn(&mut World, &mut Resources) + 'static>(&mut self, f: F) {
-        self.update_systems.push(Box::new(f));
+        self.update_systems.push(ScheduledSystem {
+            name: Cow::Borrowed(type_name::<F>()),
+            label: None,
+            enabled: true,
+            #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+            reloadable: false,
+            system: Box::new(f),
+        });
+    }
+
+    /// Adds an update system to the schedule which can be controlled at runtime by its label
+    /// through [`SystemControl`].
+    ///
+    /// Multiple systems may share a label, in which case they are controlled together.
+    pub fn add_labeled_update_system<F: Fn(&mut World, &mut Resources) + 'static>(
+        &mut self,
+        label: impl Into<Cow<'static, str>>,
+        f: F,
+    ) {
+        self.update_systems.push(ScheduledSystem {
+            name: Cow::Borrowed(type_name::<F>()),
+            label: Some(label.into()),
+            enabled: true,
+            #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+            reloadable: false,
+            system: Box::new(f),
+        });
+    }
+
+    /// Adds a system which only runs when triggered by its label through [`SystemControl::run`].
+    pub fn add_one_shot_system<F: Fn(&mut World, &mut Resources) + 'static>(
+        &mut self,
+        label: impl Into<Cow<'static, str>>,
+        f: F,
+    ) {
+        self.one_shot_systems.insert(label.into(), Box::new(f));
+    }
+
+    /// Adds an observer which runs whenever a component of type `T` is added to or removed from
+    /// an entity.
+    ///
+    /// Remove observers run after the component is gone. If its entity was despawned, the
+    /// component can still be read through [`World::despawned`] while observers run.
+    pub fn add_observer<T: Component, F: Fn(&mut World, &mut Resources, Entity) + 'static>(
+        &mut self,
+        lifecycle: Lifecycle,
+        f: F,
+    ) {
+        let type_id = TypeId::of::<T>();
+
+        if !self
+            .observed
+            .iter()
+            .any(|observed| observed.type_id == type_id)
+        {
+            self.observed.push(ObservedComponent {
+                type_id,
+                has: has_component::<T>,
+            });
+        }
+
+        let trigger = match lifecycle {
+            Lifecycle::Add => Trigger::Add(type_id),
+            Lifecycle::Remove => Trigger::Remove(type_id),
+        };
+
+        self.observers.push((trigger, Box::new(f)));
+    }
+
+    /// Adds an observer which runs whenever an entity is despawned.
+    ///
+    /// The components of the despawned entity can still be read through [`World::despawned`]
+    /// while observers run.
+    pub fn add_despawn_observer<F: Fn(&mut World, &mut Resources, Entity) + 'static>(
+        &mut self,
+        f: F,
+    ) {
+        self.observers.push((Trigger::Despawn, Box::new(f)));
+    }
+
+    /// Removes all systems registered by a hot reloadable library.
+    #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+    pub(crate) fn remove_reloadable(&mut self) {
+        self.update_systems
+            .retain(|scheduled| !scheduled.reloadable);
+
+        for label in self.reloadable_one_shot_systems.drain() {
+            self.one_shot_systems.remove(&label);
+        }
+    }
+
+    /// Adds all systems registered by a hot reloadable library into this schedule.
+    ///
+    /// Startup systems are only kept on the first load, as the world is preserved across reloads.
+    /// All names and labels are copied, as borrowed strings point into the library's memory.
+    #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+    pub(crate) fn merge_reloadable(&mut self, library: Schedule, first_load: bool) {
+        if first_load {
+            self.startup_systems.extend(
+                library
+                    .startup_systems
+                    .into_iter()
+                    .map(|(name, system)| (Cow::Owned(name.into_owned()), system)),
+            );
+        }
+
+        self.update_systems
+            .extend(
+                library
+                    .update_systems
+                    .into_iter()
+                    .map(|scheduled| ScheduledSystem {
+                        name: Cow::Owned(scheduled.name.into_owned()),
+                        label: scheduled.label.map(|label| Cow::Owned(label.into_owned())),
+                        reloadable: true,
+                        ..scheduled
+                    }),
+            );
+
+        for (label, system) in library.one_shot_systems {
+            let label = Cow::<'static, str>::Owned(label.into_owned());
+            self.reloadable_one_shot_systems.insert(label.clone());
+            self.one_shot_systems.insert(label, system);
+        }
+
+        if !library.observers.is_empty() {
+            log::warn!("observers registered by a hot reloadable library are ignored");
+        }
+    }
+
+    /// Makes the world record lifecycle events for all observed component types.
+    pub(crate) fn watch(&self, world: &mut World) {
+        world.observed = self.observed.clone();
+        world.stage_despawned = self
+            .observers
+            .iter()
+            .any(|(trigger, _)| *trigger == Trigger::Despawn);
     }
 
     /// Executes a pass of all systems.
     pub(crate) fn execute(&mut self, world: &mut World, resources: &mut Resources) {
-        for system in self.startup_systems.drain(..) {
-            system(world, resources);
+        for (name, system) in self.startup_systems.drain(..) {
+            Self::run_system(name, &system, &self.observers, world, resources);
+        }
+
+        for (name, system) in &self.pre_update_systems {
+            Self::run_system(*name, system, &self.observers, world, resources);
+        }
+
+        for scheduled in &self.update_systems {
+            if scheduled.enabled {
+                let name = scheduled
+                    .label
+                    .clone()
+                    .unwrap_or_else(|| scheduled.name.clone());
+
+                Self::run_system(name, &scheduled.system, &self.observers, world, resources);
+            }
+        }
+
+        self.apply_system_commands(world, resources);
+
+        for clear in &self.event_clearers {
+            clear(resources);
+        }
+    }
+
+    /// Applies all requests queued in [`SystemControl`], running any triggered one-shot systems.
+    fn apply_system_commands(&mut self, world: &mut World, resources: &mut Resources) {
+        let Some(control) = resources.try_get_mut::<SystemControl>() else {
+            return;
+        };
+
+        let mut triggered = Vec::new();
+
+        for command in std::mem::take(&mut control.commands) {
+            match command {
+                SystemCommand::Enable(label) => self.set_enabled(&label, true),
+                SystemCommand::Disable(label) => self.set_enabled(&label, false),
+                SystemCommand::Remove(label) => {
+                    self.update_systems
+                        .retain(|scheduled| scheduled.label.as_deref() != Some(&*label));
+                    self.one_shot_systems.remove(&label);
+                }
+                SystemCommand::Run(label) => triggered.push(label),
+            }
+        }
+
+        control.disabled = self
+            .update_systems
+            .iter()
+            .filter(|scheduled| !scheduled.enabled)
+            .filter_map(|scheduled| scheduled.label.clone())
+            .collect();
+
+        for label in triggered {
+            let Some(system) = self.one_shot_systems.get(&label) else {
+                log::warn!("no one-shot system labelled `{label}`");
+                continue;
+            };
+
+            Self::run_system(label, system, &self.observers, world, resources);
         }
+    }
+
+    /// Runs a single system followed by all observers it triggered, recording how long it took.
+    fn run_system(
+        name: impl Into<Cow<'static, str>>,
+        system: &System,
+        observers: &[(Trigger, Observer)],
+        world: &mut World,
+        resources: &mut Resources,
+    ) {
+        let start = Instant::now();
 
-        for system in &mut self.update_systems {
-            system.as_mut()(world, resources);
+        system(world, resources);
+        Self::run_observers(observers, world, resources);
+
+        if let Some(profiler) = resources.try_get_mut::<Profiler>() {
+            profiler.record(name, start, Instant::now());
         }
     }
+
+    /// Enables or disables all update systems with the given label.
+    fn set_enabled(&mut self, label: &str, enabled: bool) {
+        self.update_systems
+            .iter_mut()
+            .filter(|scheduled| scheduled.label.as_deref() == Some(label))
+            .for_each(|scheduled| scheduled.enabled = enabled);
+    }
+
+    /// Runs all observers matching the lifecycle events recorded in the world, until no new
+    /// events are recorded.
+    fn run_observers(
+        observers: &[(Trigger, Observer)],
+        world: &mut World,
+        resources: &mut Resources,
+    ) {
+        while !world.triggers.is_empty() {
+            for (trigger, entity) in std::mem::take(&mut world.triggers) {
+                for (_, observer) in observers.iter().filter(|(t, _)| *t == trigger) {
+                    observer(world, resources, entity);
+                }
+            }
+        }
+
+        world.despawned.clear();
+        world.despawned_entities.clear();
+    }
+}
+
+impl SystemControl {
+    /// Enables all systems with the given label.
+    pub fn enable(&mut self, label: impl Into<Cow<'static, str>>) {
+        self.commands.push(SystemCommand::Enable(label.into()));
+    }
+
+    /// Disables all systems with the given label.
+    pub fn disable(&mut self, label: impl Into<Cow<'static, str>>) {
+        self.commands.push(SystemCommand::Disable(label.into()));
+    }
+
+    /// Toggles all systems with the given label between enabled and disabled.
+    pub fn toggle(&mut self, label: impl Into<Cow<'static, str>>) {
+        let label = label.into();
+
+        if self.is_enabled(&label) {
+            self.disable(label);
+        } else {
+            self.enable(label);
+        }
+    }
+
+    /// Removes all systems with the given label from the schedule.
+    pub fn remove(&mut self, label: impl Into<Cow<'static, str>>) {
+        self.commands.push(SystemCommand::Remove(label.into()));
+    }
+
+    /// Triggers the one-shot system with the given label to run.
+    pub fn run(&mut self, label: impl Into<Cow<'static, str>>) {
+        self.commands.push(SystemCommand::Run(label.into()));
+    }
+
+    /// Returns if the systems with the given label were enabled as of the end of the last update
+    /// cycle.
+    pub fn is_enabled(&self, label: &str) -> bool {
+        !self.disabled.contains(label)
+    }
+
+    /// Copies all borrowed labels, so none of them point into a library about to be unloaded.
+    #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+    pub(crate) fn own_labels(&mut self) {
+        let own = |label: &mut Cow<'static, str>| *label = Cow::Owned(label.to_string());
+
+        for command in &mut self.commands {
+            match command {
+                SystemCommand::Enable(label)
+                | SystemCommand::Disable(label)
+                | SystemCommand::Remove(label)
+                | SystemCommand::Run(label) => own(label),
+            }
+        }
+
+        self.disabled = self
+            .disabled
+            .drain()
+            .map(|label| Cow::Owned(label.into_owned()))
+            .collect();
+    }
 }
 
 impl World {
     /// Creates a new, empty [`World`].
     pub fn new() -> Self {
-        Self(hecs::World::new())
+        Self {
+            inner: hecs::World::new(),
+            observed: Vec::new(),
+            triggers: Vec::new(),
+            stage_despawned: false,
+            despawned: hecs::World::new(),
+            despawned_entities: HashMap::new(),
+        }
+    }
+
+    /// Spawns an entity with the given components, returning its handle.
+    pub fn spawn(&mut self, components: impl DynamicBundle) -> Entity {
+        let entity = self.inner.spawn(components);
+        self.record_changes(entity, &[]);
+        entity
+    }
+
+    /// Adds components to an entity, replacing any existing components of the same types.
+    pub fn insert(
+        &mut self,
+        entity: Entity,
+        components: impl DynamicBundle,
+    ) -> Result<(), NoSuchEntity> {
+        let before = self.observed_components(entity);
+        self.inner.insert(entity, components)?;
+        self.record_changes(entity, &before);
+        Ok(())
+    }
+
+    /// Adds a single component to an entity, replacing any existing component of the same type.
+    pub fn insert_one(
+        &mut self,
+        entity: Entity,
+        component: impl Component,
+    ) -> Result<(), NoSuchEntity> {
+        let before = self.observed_components(entity);
+        self.inner.insert_one(entity, component)?;
+        self.record_changes(entity, &before);
+        Ok(())
+    }
+
+    /// Removes and returns a bundle of components from an entity.
+    pub fn remove<T: Bundle + 'static>(&mut self, entity: Entity) -> Result<T, ComponentError> {
+        let before = self.observed_components(entity);
+        let bundle = self.inner.remove::<T>(entity)?;
+        self.record_changes(entity, &before);
+        Ok(bundle)
+    }
+
+    /// Removes and returns a single component from an entity.
+    pub fn remove_one<T: Component>(&mut self, entity: Entity) -> Result<T, ComponentError> {
+        let before = self.observed_components(entity);
+        let component = self.inner.remove_one::<T>(entity)?;
+        self.record_changes(entity, &before);
+        Ok(component)
+    }
+
+    /// Despawns an entity along with all of its components.
+    ///
+    /// If the entity had any observed components or despawn observers are registered, all of its
+    /// components are kept around until observers have run, readable through
+    /// [`World::despawned`].
+    pub fn despawn(&mut self, entity: Entity) -> Result<(), NoSuchEntity> {
+        let before = self.observed_components(entity);
+
+        if before.is_empty() && !self.stage_despawned {
+            self.inner.despawn(entity)?;
+        } else {
+            let components = self.inner.take(entity)?;
+            let staged = self.despawned.spawn(components);
+            self.despawned_entities.insert(entity, staged);
+        }
+
+        self.triggers.extend(
+            before
+                .into_iter()
+                .map(|type_id| (Trigger::Remove(type_id), entity)),
+        );
+        self.triggers.push((Trigger::Despawn, entity));
+
+        Ok(())
+    }
+
+    /// Despawns all entities in the world.
+    pub fn clear(&mut self) {
+        let entities = self
+            .inner
+            .iter()
+            .map(|entity| entity.entity())
+            .collect::<Vec<_>>();
+
+        for entity in entities {
+            let _ = self.despawn(entity);
+        }
+    }
+
+    /// Returns a component of an entity despawned since observers were last run, if it had one.
+    ///
+    /// Only entities with at least one observed component are kept, or every entity if despawn
+    /// observers are registered, which lets remove and despawn observers inspect the final state
+    /// of the entity.
+    pub fn despawned<T: Component>(&self, entity: Entity) -> Option<hecs::Ref<'_, T>> {
+        let staged = *self.despawned_entities.get(&entity)?;
+        self.despawned.get::<&T>(staged).ok()
+    }
+
+    /// Returns the observed component types an entity currently has.
+    fn observed_components(&self, entity: Entity) -> Vec<TypeId> {
+        self.observed
+            .iter()
+            .filter(|observed| (observed.has)(&self.inner, entity))
+            .map(|observed| observed.type_id)
+            .collect()
+    }
+
+    /// Records the lifecycle events of an entity, given the observed components it had before.
+    fn record_changes(&mut self, entity: Entity, before: &[TypeId]) {
+        if self.observed.is_empty() {
+            return;
+        }
+
+        let after = self.observed_components(entity);
+
+        for type_id in &after {
+            if !before.contains(type_id) {
+                self.triggers.push((Trigger::Add(*type_id), entity));
+            }
+        }
+
+        for type_id in before {
+            if !after.contains(type_id) {
+                self.triggers.push((Trigger::Remove(*type_id), entity));
+            }
+        }
     }
 }
 
@@ -77,6 +656,59 @@ impl Resources {
     pub fn get_mut<T: 'static>(&mut self) -> &mut T {
         self.0.get_mut::<T>().unwrap()
     }
+
+    /// Returns an immutable reference to a resource of a given type, if it exists.
+    pub fn try_get<T: 'static>(&self) -> Option<&T> {
+        self.0.get::<T>()
+    }
+
+    /// Returns a mutable reference to a resource of a given type, if it exists.
+    pub fn try_get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.0.get_mut::<T>()
+    }
+
+    /// Removes a resource of a given type, returning it if it existed.
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.0.remove::<T>()
+    }
+}
+
+impl<T> Events<T> {
+    /// Creates a new, empty queue of [`Events`].
+    pub fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    /// Sends an event to all systems reading this queue.
+    pub fn send(&mut self, event: T) {
+        self.events.push(event);
+    }
+
+    /// Returns an iterator over all events sent this frame.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &T> {
+        self.events.iter()
+    }
+
+    /// Returns the number of events sent this frame.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns if no events were sent this frame.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Removes all events sent this frame.
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Default for Resources {
@@ -89,12 +721,165 @@ impl Deref for World {
     type Target = hecs::World;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.inner
     }
 }
 
 impl DerefMut for World {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.inner
+    }
+}
+
+/// Returns if an entity has a component of type `T`.
+fn has_component<T: Component>(world: &hecs::World, entity: Entity) -> bool {
+    world.entity(entity).is_ok_and(|entity| entity.has::<T>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A component with observers registered for it.
+    struct Health(u32);
+
+    /// A component without any observers, pointing at another entity.
+    struct Parent(Entity);
+
+    /// The lifecycle events seen by observers, in the order they ran.
+    #[derive(Default)]
+    struct Log(Vec<String>);
+
+    fn log(resources: &mut Resources, entry: String) {
+        resources.get_mut::<Log>().0.push(entry);
+    }
+
+    /// Creates a schedule with add, remove and despawn observers which log what they see, along
+    /// with a world watched by it.
+    fn observed_schedule() -> (Schedule, World, Resources) {
+        let mut schedule = Schedule::new();
+
+        schedule.add_observer::<Health, _>(Lifecycle::Add, |world, resources, entity| {
+            let health = world.get::<&Health>(entity).map(|health| health.0).ok();
+            log(resources, format!("add {} {health:?}", entity.id()));
+        });
+
+        schedule.add_observer::<Health, _>(Lifecycle::Remove, |world, resources, entity| {
+            let health = world.despawned::<Health>(entity).map(|health| health.0);
+            log(resources, format!("remove {} {health:?}", entity.id()));
+        });
+
+        schedule.add_despawn_observer(|world, resources, entity| {
+            let parent = world
+                .despawned::<Parent>(entity)
+                .map(|parent| parent.0.id());
+            log(resources, format!("despawn {} {parent:?}", entity.id()));
+
+            // despawning from an observer runs the observers of the children in the same pass
+            let children = world
+                .query::<&Parent>()
+                .iter()
+                .filter(|(_, child)| child.0 == entity)
+                .map(|(child, _)| child)
+                .collect::<Vec<_>>();
+
+            for child in children {
+                let _ = world.despawn(child);
+            }
+        });
+
+        let mut world = World::new();
+        schedule.watch(&mut world);
+
+        let mut resources = Resources::new();
+        resources.insert(Log::default());
+
+        (schedule, world, resources)
+    }
+
+    fn run(
+        schedule: &mut Schedule,
+        world: &mut World,
+        resources: &mut Resources,
+        system: impl Fn(&mut World, &mut Resources) + 'static,
+    ) -> Vec<String> {
+        schedule.add_startup_system(system);
+        schedule.execute(world, resources);
+        std::mem::take(&mut resources.get_mut::<Log>().0)
+    }
+
+    #[test]
+    fn add_observers_run_once_per_added_component() {
+        let (mut schedule, mut world, mut resources) = observed_schedule();
+
+        let log = run(&mut schedule, &mut world, &mut resources, |world, _| {
+            let a = world.spawn((Health(3),));
+            let b = world.spawn(());
+            world.insert_one(b, Health(5)).unwrap();
+            world.insert_one(a, Health(4)).unwrap();
+        });
+
+        assert_eq!(log, ["add 0 Some(4)", "add 1 Some(5)"]);
+    }
+
+    #[test]
+    fn remove_observers_see_despawned_components() {
+        let (mut schedule, mut world, mut resources) = observed_schedule();
+        let a = world.spawn((Health(3),));
+        let b = world.spawn((Health(7),));
+
+        let log = run(
+            &mut schedule,
+            &mut world,
+            &mut resources,
+            move |world, _| {
+                world.remove_one::<Health>(a).unwrap();
+                world.despawn(b).unwrap();
+            },
+        );
+
+        // the spawns above are only observed after the first system, by which point `a` lost its
+        // component to the caller, while the despawned `b` keeps it until observers have run
+        assert_eq!(
+            log,
+            [
+                "add 0 None",
+                "add 1 None",
+                "remove 0 None",
+                "remove 1 Some(7)",
+                "despawn 1 None"
+            ]
+        );
+        assert!(world.despawned::<Health>(b).is_none());
+    }
+
+    #[test]
+    fn despawn_observers_see_unobserved_components() {
+        let (mut schedule, mut world, mut resources) = observed_schedule();
+        let root = world.spawn(());
+        let parent = world.spawn((Parent(root),));
+        let child = world.spawn((Parent(parent),));
+        let grandchild = world.spawn((Parent(child),));
+
+        let log = run(
+            &mut schedule,
+            &mut world,
+            &mut resources,
+            move |world, _| {
+                world.despawn(parent).unwrap();
+            },
+        );
+
+        assert_eq!(
+            log,
+            [
+                format!("despawn {} Some({})", parent.id(), root.id()),
+                format!("despawn {} Some({})", child.id(), parent.id()),
+                format!("despawn {} Some({})", grandchild.id(), child.id()),
+            ]
+        );
+        assert!(world.contains(root));
+        assert!(!world.contains(grandchild));
+        assert!(world.despawned::<Parent>(parent).is_none());
     }
 }
@@ -0,0 +1,186 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context;
+use libloading::Library;
+
+use crate::{
+    ecs::{Resources, Schedule, SystemControl},
+    profiler::Profiler,
+    timer::DelayedCommands,
+};
+
+/// The name of the function every gameplay library must export to register its systems.
+///
+/// ```ignore
+/// #[unsafe(no_mangle)]
+/// pub fn ferret_register(schedule: &mut Schedule) {
+///     schedule.add_update_system(rotate);
+/// }
+/// ```
+pub const REGISTER_SYMBOL: &str = "ferret_register";
+
+/// The signature of the registration function exported by a gameplay library.
+pub type RegisterFn = fn(&mut Schedule);
+
+/// The settings for hot reloading gameplay systems, inserted by
+/// [`with_hot_reload`](crate::application::ApplicationBuilder::with_hot_reload).
+#[derive(Debug, Clone)]
+pub struct HotReloadSettings {
+    /// The path of the gameplay library as written by the compiler.
+    pub path: PathBuf,
+}
+
+/// Watches a gameplay library, re-registering its systems into the schedule whenever it changes.
+///
+/// The library must be built by the same compiler against the same build of the engine, as systems
+/// are passed across the boundary using the unstable Rust ABI. Components and resources should be
+/// defined outside of the library, as their drop glue would otherwise point into unloaded code.
+///
+/// Before a reload, all system names and labels are copied, all profiler spans are discarded and
+/// all pending [`DelayedCommands`] are dropped, including those queued by the engine itself, as
+/// there's no telling which closures point into the library. Any other `'static` data handed out
+/// by the library, such as strings or trait objects stored in components or resources, is still
+/// left dangling once it is unloaded.
+pub(crate) struct HotReload {
+    /// The path of the gameplay library as written by the compiler.
+    path: PathBuf,
+    /// The modification time of the currently loaded library.
+    modified: Option<SystemTime>,
+    /// The currently loaded copy of the library.
+    library: Option<Library>,
+    /// The path of the currently loaded copy of the library.
+    copy: Option<PathBuf>,
+    /// The number of times the library has been loaded, used to name each copy uniquely.
+    generation: u32,
+}
+
+impl HotReload {
+    /// How long the library must remain unmodified before being reloaded, to avoid loading a
+    /// partially written file.
+    const SETTLE_TIME: Duration = Duration::from_millis(250);
+
+    /// Creates a new [`HotReload`] watching the given library.
+    pub fn new(settings: HotReloadSettings) -> Self {
+        Self {
+            path: settings.path,
+            modified: None,
+            library: None,
+            copy: None,
+            generation: 0,
+        }
+    }
+
+    /// Reloads the library into the schedule if it has changed since it was last loaded.
+    pub fn poll(&mut self, schedule: &mut Schedule, resources: &mut Resources) {
+        let Ok(modified) = std::fs::metadata(&self.path).and_then(|meta| meta.modified()) else {
+            return;
+        };
+
+        let settled = modified
+            .elapsed()
+            .is_ok_and(|elapsed| elapsed >= Self::SETTLE_TIME);
+
+        if self.modified == Some(modified) || !settled {
+            return;
+        }
+
+        self.modified = Some(modified);
+
+        match self.reload(schedule, resources) {
+            Ok(()) => log::info!("reloaded gameplay library {}", self.path.display()),
+            Err(e) => log::error!("failed to reload gameplay library: {e:#}"),
+        }
+    }
+
+    /// Unloads the current library and loads a fresh copy of it, re-registering all systems.
+    fn reload(&mut self, schedule: &mut Schedule, resources: &mut Resources) -> anyhow::Result<()> {
+        // all systems must be dropped before the code they point into is unloaded
+        schedule.remove_reloadable();
+        Self::release(resources);
+        self.unload();
+
+        let copy = self.copy_path();
+        std::fs::copy(&self.path, &copy)
+            .with_context(|| format!("while copying library to {}", copy.display()))?;
+
+        // SAFETY: the library is a private copy built from the gameplay crate, whose initializers
+        // are trusted. It is kept alive until its systems are dropped and every string or closure
+        // the engine knows to have come from it is copied or dropped, see `release`; anything else
+        // the library hands out is documented as unsupported on `HotReload`
+        let library = unsafe { Library::new(&copy) }.context("while loading library")?;
+        let mut library_schedule = Schedule::new();
+
+        {
+            // SAFETY: the symbol is exported by the library as a `RegisterFn`, and the reference
+            // to it is dropped before the library is moved or unloaded
+            let register = unsafe { library.get::<RegisterFn>(REGISTER_SYMBOL.as_bytes()) }
+                .with_context(|| format!("while looking up `{REGISTER_SYMBOL}`"))?;
+
+            (*register)(&mut library_schedule);
+        }
+
+        schedule.merge_reloadable(library_schedule, self.generation == 0);
+
+        self.library = Some(library);
+        self.copy = Some(copy);
+        self.generation += 1;
+
+        Ok(())
+    }
+
+    /// Copies or drops all engine state which may point into the library, before it is unloaded.
+    fn release(resources: &mut Resources) {
+        if let Some(profiler) = resources.try_get_mut::<Profiler>() {
+            profiler.clear();
+        }
+
+        if let Some(control) = resources.try_get_mut::<SystemControl>() {
+            control.own_labels();
+        }
+
+        if let Some(commands) = resources.try_get_mut::<DelayedCommands>()
+            && !commands.is_empty()
+        {
+            log::warn!(
+                "dropped {} delayed commands as the gameplay library was reloaded",
+                commands.len()
+            );
+            commands.clear();
+        }
+    }
+
+    /// Unloads the current copy of the library, if any, and removes it from disk.
+    fn unload(&mut self) {
+        self.library = None;
+
+        if let Some(copy) = self.copy.take() {
+            let _ = std::fs::remove_file(copy);
+        }
+    }
+
+    /// Returns a unique path to copy the library to before loading it, so that the compiler can
+    /// overwrite the original and the platform loader doesn't return a cached handle.
+    fn copy_path(&self) -> PathBuf {
+        let stem = self
+            .path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("gameplay");
+
+        let file_name = match self.path.extension().and_then(|ext| ext.to_str()) {
+            Some(extension) => format!("{stem}-{}.{extension}", self.generation),
+            None => format!("{stem}-{}", self.generation),
+        };
+
+        std::env::temp_dir().join(file_name)
+    }
+}
+
+impl Drop for HotReload {
+    fn drop(&mut self) {
+        self.unload();
+    }
+}
@@ -0,0 +1,307 @@
+use std::{borrow::Cow, collections::BTreeMap, path::Path};
+
+use anyhow::Context;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+use crate::input::{
+    InputState,
+    gamepad::{GamepadButton, GamepadStick},
+};
+
+/// A single physical input an action or axis can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    /// A keyboard key, identified by its physical location.
+    Key(KeyCode),
+    /// A mouse button.
+    Mouse(MouseButton),
+    /// A button on any connected gamepad.
+    Gamepad(GamepadButton),
+}
+
+/// The bindings of a 2D axis, such as WASD mapped to a movement direction.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Axis2D {
+    /// The bindings pushing the axis towards positive y.
+    pub up: Vec<Binding>,
+    /// The bindings pushing the axis towards negative y.
+    pub down: Vec<Binding>,
+    /// The bindings pushing the axis towards negative x.
+    pub left: Vec<Binding>,
+    /// The bindings pushing the axis towards positive x.
+    pub right: Vec<Binding>,
+    /// The gamepad stick driving the axis, read from any connected gamepad.
+    #[serde(default)]
+    pub stick: Option<GamepadStick>,
+}
+
+/// Maps named actions and axes onto physical inputs, which can be rebound at runtime and stored in
+/// a human readable JSON config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputMap {
+    /// The bindings of every action, keyed by the action's name.
+    actions: BTreeMap<Cow<'static, str>, Vec<Binding>>,
+    /// The bindings of every 2D axis, keyed by the axis' name.
+    axes: BTreeMap<Cow<'static, str>, Axis2D>,
+}
+
+impl Binding {
+    /// Returns if the bound input was pressed this frame.
+    pub fn pressed(&self, input: &InputState) -> bool {
+        match *self {
+            Self::Key(code) => input.key_pressed(code),
+            Self::Mouse(button) => input.mouse_pressed(button),
+            Self::Gamepad(button) => input.gamepad_pressed(button),
+        }
+    }
+
+    /// Returns if the bound input was released this frame.
+    pub fn released(&self, input: &InputState) -> bool {
+        match *self {
+            Self::Key(code) => input.key_released(code),
+            Self::Mouse(button) => input.mouse_released(button),
+            Self::Gamepad(button) => input.gamepad_released(button),
+        }
+    }
+
+    /// Returns if the bound input is currently being held down.
+    pub fn held(&self, input: &InputState) -> bool {
+        match *self {
+            Self::Key(code) => input.key_held(code),
+            Self::Mouse(button) => input.mouse_held(button),
+            Self::Gamepad(button) => input.gamepad_held(button),
+        }
+    }
+}
+
+impl From<KeyCode> for Binding {
+    fn from(code: KeyCode) -> Self {
+        Self::Key(code)
+    }
+}
+
+impl From<MouseButton> for Binding {
+    fn from(button: MouseButton) -> Self {
+        Self::Mouse(button)
+    }
+}
+
+impl From<GamepadButton> for Binding {
+    fn from(button: GamepadButton) -> Self {
+        Self::Gamepad(button)
+    }
+}
+
+impl Axis2D {
+    /// Creates a new [`Axis2D`] bound to a single input in each direction.
+    pub fn new(
+        up: impl Into<Binding>,
+        down: impl Into<Binding>,
+        left: impl Into<Binding>,
+        right: impl Into<Binding>,
+    ) -> Self {
+        Self {
+            up: vec![up.into()],
+            down: vec![down.into()],
+            left: vec![left.into()],
+            right: vec![right.into()],
+            stick: None,
+        }
+    }
+
+    /// Drives the axis with the given gamepad stick, in addition to its other bindings.
+    pub fn with_stick(mut self, stick: GamepadStick) -> Self {
+        self.stick = Some(stick);
+        self
+    }
+
+    /// Creates a new [`Axis2D`] bound to the WASD keys.
+    pub fn wasd() -> Self {
+        Self::new(KeyCode::KeyW, KeyCode::KeyS, KeyCode::KeyA, KeyCode::KeyD)
+    }
+
+    /// Creates a new [`Axis2D`] bound to the arrow keys.
+    pub fn arrows() -> Self {
+        Self::new(
+            KeyCode::ArrowUp,
+            KeyCode::ArrowDown,
+            KeyCode::ArrowLeft,
+            KeyCode::ArrowRight,
+        )
+    }
+
+    /// Returns the current value of the axis, with a length of at most one.
+    pub fn value(&self, input: &InputState) -> Vec2 {
+        let direction = |bindings: &[Binding]| {
+            if bindings.iter().any(|binding| binding.held(input)) {
+                1.0
+            } else {
+                0.0
+            }
+        };
+
+        let digital = Vec2::new(
+            direction(&self.right) - direction(&self.left),
+            direction(&self.up) - direction(&self.down),
+        );
+
+        let analog = self.stick.map_or(Vec2::ZERO, |stick| {
+            input
+                .gamepads()
+                .map(|(_, gamepad)| gamepad.stick(stick))
+                .fold(
+                    Vec2::ZERO,
+                    |a, b| if b.length() > a.length() { b } else { a },
+                )
+        });
+
+        (digital + analog).clamp_length_max(1.0)
+    }
+}
+
+impl InputMap {
+    /// Creates a new, empty [`InputMap`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds an action to the given inputs, replacing any previous bindings.
+    pub fn with_action<B: Into<Binding>>(
+        mut self,
+        action: impl Into<Cow<'static, str>>,
+        bindings: impl IntoIterator<Item = B>,
+    ) -> Self {
+        self.rebind_action(action, bindings);
+        self
+    }
+
+    /// Binds a 2D axis, replacing any previous bindings.
+    pub fn with_axis(mut self, axis: impl Into<Cow<'static, str>>, bindings: Axis2D) -> Self {
+        self.rebind_axis(axis, bindings);
+        self
+    }
+
+    /// Adds an input to the bindings of an action.
+    pub fn bind_action(
+        &mut self,
+        action: impl Into<Cow<'static, str>>,
+        binding: impl Into<Binding>,
+    ) {
+        let bindings = self.actions.entry(action.into()).or_default();
+        let binding = binding.into();
+
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Removes an input from the bindings of an action.
+    pub fn unbind_action(&mut self, action: &str, binding: impl Into<Binding>) {
+        let binding = binding.into();
+
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|bound| *bound != binding);
+        }
+    }
+
+    /// Replaces all bindings of an action with the given inputs.
+    pub fn rebind_action<B: Into<Binding>>(
+        &mut self,
+        action: impl Into<Cow<'static, str>>,
+        bindings: impl IntoIterator<Item = B>,
+    ) {
+        self.actions.insert(
+            action.into(),
+            bindings.into_iter().map(Into::into).collect(),
+        );
+    }
+
+    /// Replaces all bindings of a 2D axis.
+    pub fn rebind_axis(&mut self, axis: impl Into<Cow<'static, str>>, bindings: Axis2D) {
+        self.axes.insert(axis.into(), bindings);
+    }
+
+    /// Returns the bindings of an action.
+    pub fn action_bindings(&self, action: &str) -> &[Binding] {
+        self.actions
+            .get(action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the bindings of a 2D axis.
+    pub fn axis_bindings(&self, axis: &str) -> Option<&Axis2D> {
+        self.axes.get(axis)
+    }
+
+    /// Returns a mutable reference to the bindings of a 2D axis.
+    pub fn axis_bindings_mut(&mut self, axis: &str) -> Option<&mut Axis2D> {
+        self.axes.get_mut(axis)
+    }
+
+    /// Returns an iterator over the names of all bound actions.
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(|action| action.as_ref())
+    }
+
+    /// Returns an iterator over the names of all bound axes.
+    pub fn axes(&self) -> impl Iterator<Item = &str> {
+        self.axes.keys().map(|axis| axis.as_ref())
+    }
+
+    /// Returns if any input bound to the action was pressed this frame.
+    pub fn pressed(&self, input: &InputState, action: &str) -> bool {
+        self.action_bindings(action)
+            .iter()
+            .any(|binding| binding.pressed(input))
+    }
+
+    /// Returns if any input bound to the action was released this frame.
+    pub fn released(&self, input: &InputState, action: &str) -> bool {
+        self.action_bindings(action)
+            .iter()
+            .any(|binding| binding.released(input))
+    }
+
+    /// Returns if any input bound to the action is currently being held down.
+    pub fn held(&self, input: &InputState, action: &str) -> bool {
+        self.action_bindings(action)
+            .iter()
+            .any(|binding| binding.held(input))
+    }
+
+    /// Returns the current value of a 2D axis, or zero if it isn't bound.
+    pub fn axis(&self, input: &InputState, axis: &str) -> Vec2 {
+        self.axes
+            .get(axis)
+            .map_or(Vec2::ZERO, |bindings| bindings.value(input))
+    }
+
+    /// Parses an [`InputMap`] from its JSON representation.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        serde_json::from_str(json).context("while parsing input map")
+    }
+
+    /// Converts this [`InputMap`] into its pretty printed JSON representation.
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        serde_json::to_string_pretty(self).context("while serializing input map")
+    }
+
+    /// Reads and parses an [`InputMap`] from the config file at the given path.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("while reading input config {}", path.display()))?;
+
+        Self::from_json(&json)
+    }
+
+    /// Writes this [`InputMap`] to the config file at the given path.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json()?)
+            .with_context(|| format!("while writing input config {}", path.display()))
+    }
+}
@@ -0,0 +1,552 @@
+use std::{borrow::Cow, collections::VecDeque, f32::consts::FRAC_PI_4};
+
+use glam::Vec2;
+
+use crate::{
+    ecs::{Resources, World},
+    input::{InputState, actions::InputMap},
+    timer::FrameTimer,
+};
+
+/// One of the eight directions a 2D axis can point in, as seen on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Straight up, towards the top of the screen.
+    Up,
+    /// Diagonally up and to the right.
+    UpRight,
+    /// Straight to the right.
+    Right,
+    /// Diagonally down and to the right.
+    DownRight,
+    /// Straight down, towards the bottom of the screen.
+    Down,
+    /// Diagonally down and to the left.
+    DownLeft,
+    /// Straight to the left.
+    Left,
+    /// Diagonally up and to the left.
+    UpLeft,
+}
+
+/// A single input remembered by the [`InputBuffer`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BufferedInput {
+    /// A named action of the [`InputMap`] was pressed.
+    Action(Cow<'static, str>),
+    /// The direction axis started pointing in a new direction.
+    Direction(Direction),
+}
+
+/// How long the [`InputBuffer`] remembers presses for, or how quickly a [`Sequence`] must be
+/// entered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BufferWindow {
+    /// The given number of frames.
+    Frames(u64),
+    /// The given number of real seconds, e.g. `0.3` for 300ms.
+    Seconds(f64),
+}
+
+/// A press remembered by the [`InputBuffer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BufferedPress {
+    /// The input which was pressed.
+    pub input: BufferedInput,
+    /// The frame the input was pressed on.
+    pub frame: u64,
+    /// The real time in seconds the input was pressed at.
+    pub time: f64,
+    /// Whether the press was already used up by [`InputBuffer::consume`] or
+    /// [`InputBuffer::consume_sequence`].
+    pub consumed: bool,
+}
+
+/// A sequence of inputs which must be pressed in order within a time window, such as a fighting
+/// game special move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sequence {
+    /// The steps of the sequence, each requiring all of its inputs in any order.
+    steps: Vec<Vec<BufferedInput>>,
+    /// The longest time allowed between the first and the last press.
+    window: BufferWindow,
+}
+
+/// Remembers recent presses of all actions and directions for a configurable window, to allow
+/// lenient input such as jump buffering and to detect sequences and combos.
+///
+/// The buffer is updated before all update systems each frame. Presses are forgotten once they
+/// fall out of its window, so it must be at least as long as the longest [`Sequence`].
+#[derive(Debug, Clone)]
+pub struct InputBuffer {
+    /// All remembered presses, from oldest to newest.
+    presses: VecDeque<BufferedPress>,
+    /// How long presses are remembered for.
+    window: BufferWindow,
+
+    /// The axis of the [`InputMap`] whose direction changes are remembered, if any.
+    direction_axis: Option<Cow<'static, str>>,
+    /// The length the direction axis must exceed to point in a direction.
+    direction_deadzone: f32,
+    /// The direction the axis currently points in.
+    direction: Option<Direction>,
+
+    /// The current frame.
+    frame: u64,
+    /// The real time in seconds at the start of the current frame.
+    time: f64,
+}
+
+impl Direction {
+    /// Returns the direction closest to the given axis value, if it is longer than the deadzone.
+    pub fn from_axis(value: Vec2, deadzone: f32) -> Option<Self> {
+        if value.length() <= deadzone.max(f32::EPSILON) {
+            return None;
+        }
+
+        // every direction covers a 45 degree slice of the circle centered on it
+        let slice = (value.y.atan2(value.x) / FRAC_PI_4).round() as i32;
+
+        Some(match slice.rem_euclid(8) {
+            0 => Self::Right,
+            1 => Self::UpRight,
+            2 => Self::Up,
+            3 => Self::UpLeft,
+            4 => Self::Left,
+            5 => Self::DownLeft,
+            6 => Self::Down,
+            _ => Self::DownRight,
+        })
+    }
+
+    /// Returns the direction mirrored horizontally, e.g. to turn "forward" into
+    /// [`Direction::Left`] for a character facing left.
+    pub fn mirrored(self) -> Self {
+        match self {
+            Self::UpRight => Self::UpLeft,
+            Self::Right => Self::Left,
+            Self::DownRight => Self::DownLeft,
+            Self::DownLeft => Self::DownRight,
+            Self::Left => Self::Right,
+            Self::UpLeft => Self::UpRight,
+            Self::Up | Self::Down => self,
+        }
+    }
+
+    /// Returns the unit vector pointing in this direction, with y pointing up.
+    pub fn to_vec2(self) -> Vec2 {
+        let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+
+        match self {
+            Self::Up => Vec2::Y,
+            Self::UpRight => Vec2::new(diagonal, diagonal),
+            Self::Right => Vec2::X,
+            Self::DownRight => Vec2::new(diagonal, -diagonal),
+            Self::Down => Vec2::NEG_Y,
+            Self::DownLeft => Vec2::new(-diagonal, -diagonal),
+            Self::Left => Vec2::NEG_X,
+            Self::UpLeft => Vec2::new(-diagonal, diagonal),
+        }
+    }
+}
+
+impl From<Direction> for BufferedInput {
+    fn from(direction: Direction) -> Self {
+        Self::Direction(direction)
+    }
+}
+
+impl From<&'static str> for BufferedInput {
+    fn from(action: &'static str) -> Self {
+        Self::Action(action.into())
+    }
+}
+
+impl From<String> for BufferedInput {
+    fn from(action: String) -> Self {
+        Self::Action(action.into())
+    }
+}
+
+impl BufferWindow {
+    /// Returns if the press happened no longer than this window before the given frame and time.
+    fn contains(self, press: &BufferedPress, frame: u64, time: f64) -> bool {
+        match self {
+            Self::Frames(frames) => frame.saturating_sub(press.frame) <= frames,
+            Self::Seconds(seconds) => time - press.time <= seconds,
+        }
+    }
+}
+
+impl Sequence {
+    /// Creates a new, empty [`Sequence`] which must be entered within the given window.
+    pub fn new(window: BufferWindow) -> Self {
+        Self {
+            steps: Vec::new(),
+            window,
+        }
+    }
+
+    /// Appends a step requiring the given input.
+    pub fn then(mut self, input: impl Into<BufferedInput>) -> Self {
+        self.steps.push(vec![input.into()]);
+        self
+    }
+
+    /// Appends a step requiring all given inputs in any order, such as forward plus attack.
+    pub fn then_all<I: Into<BufferedInput>>(mut self, inputs: impl IntoIterator<Item = I>) -> Self {
+        self.steps
+            .push(inputs.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Returns the steps of the sequence, each requiring all of its inputs in any order.
+    pub fn steps(&self) -> &[Vec<BufferedInput>] {
+        &self.steps
+    }
+
+    /// Returns the longest time allowed between the first and the last press.
+    pub fn window(&self) -> BufferWindow {
+        self.window
+    }
+}
+
+impl InputBuffer {
+    /// The default length the direction axis must exceed to point in a direction.
+    pub const DEFAULT_DIRECTION_DEADZONE: f32 = 0.5;
+
+    /// Creates a new [`InputBuffer`] remembering presses for the given window.
+    pub fn new(window: BufferWindow) -> Self {
+        Self {
+            presses: VecDeque::new(),
+            window,
+            direction_axis: None,
+            direction_deadzone: Self::DEFAULT_DIRECTION_DEADZONE,
+            direction: None,
+            frame: 0,
+            time: 0.0,
+        }
+    }
+
+    /// Remembers the direction changes of the given axis of the [`InputMap`], e.g. for motion
+    /// inputs.
+    pub fn with_direction_axis(mut self, axis: impl Into<Cow<'static, str>>) -> Self {
+        self.direction_axis = Some(axis.into());
+        self
+    }
+
+    /// Returns how long presses are remembered for.
+    pub fn window(&self) -> BufferWindow {
+        self.window
+    }
+
+    /// Sets how long presses are remembered for.
+    pub fn set_window(&mut self, window: BufferWindow) {
+        self.window = window;
+    }
+
+    /// Returns the axis whose direction changes are remembered, if any.
+    pub fn direction_axis(&self) -> Option<&str> {
+        self.direction_axis.as_deref()
+    }
+
+    /// Sets the axis whose direction changes are remembered.
+    pub fn set_direction_axis(&mut self, axis: Option<Cow<'static, str>>) {
+        self.direction_axis = axis;
+        self.direction = None;
+    }
+
+    /// Returns the length the direction axis must exceed to point in a direction.
+    pub fn direction_deadzone(&self) -> f32 {
+        self.direction_deadzone
+    }
+
+    /// Sets the length the direction axis must exceed to point in a direction.
+    pub fn set_direction_deadzone(&mut self, deadzone: f32) {
+        self.direction_deadzone = deadzone;
+    }
+
+    /// Returns the direction the axis currently points in, if any.
+    pub fn direction(&self) -> Option<Direction> {
+        self.direction
+    }
+
+    /// Returns all remembered presses, from oldest to newest.
+    pub fn presses(&self) -> impl Iterator<Item = &BufferedPress> {
+        self.presses.iter()
+    }
+
+    /// Returns if the given input was pressed within the buffer's window and not yet consumed.
+    pub fn pressed(&self, input: impl Into<BufferedInput>) -> bool {
+        self.pressed_within(input, self.window)
+    }
+
+    /// Returns if the given input was pressed within the given window, which is limited by the
+    /// buffer's own window, and not yet consumed.
+    pub fn pressed_within(&self, input: impl Into<BufferedInput>, window: BufferWindow) -> bool {
+        let input = input.into();
+
+        self.presses.iter().rev().any(|press| {
+            !press.consumed && press.input == input && window.contains(press, self.frame, self.time)
+        })
+    }
+
+    /// Consumes the most recent press of the given input within the buffer's window, returning
+    /// if there was one. This allows a press to trigger something later, but only once, e.g. a jump
+    /// pressed shortly before landing.
+    pub fn consume(&mut self, input: impl Into<BufferedInput>) -> bool {
+        let input = input.into();
+
+        match self
+            .presses
+            .iter_mut()
+            .rev()
+            .find(|press| !press.consumed && press.input == input)
+        {
+            Some(press) => {
+                press.consumed = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns if the sequence was completed by a press this frame.
+    pub fn sequence(&self, sequence: &Sequence) -> bool {
+        self.find_sequence(sequence)
+            .is_some_and(|matched| self.presses[matched[0]].frame == self.frame)
+    }
+
+    /// Consumes all presses of the sequence if it was completed within the buffer's window,
+    /// returning if it was.
+    pub fn consume_sequence(&mut self, sequence: &Sequence) -> bool {
+        let Some(matched) = self.find_sequence(sequence) else {
+            return false;
+        };
+
+        for index in matched {
+            self.presses[index].consumed = true;
+        }
+
+        true
+    }
+
+    /// Forgets all remembered presses.
+    pub fn clear(&mut self) {
+        self.presses.clear();
+    }
+
+    /// Finds the most recent completion of the sequence, returning the indices of its presses
+    /// from newest to oldest.
+    fn find_sequence(&self, sequence: &Sequence) -> Option<Vec<usize>> {
+        if sequence.steps.is_empty() {
+            return None;
+        }
+
+        let mut matched = Vec::new();
+        let mut remaining = self.presses.len();
+
+        // match from the last step backwards, so the most recent presses are preferred
+        for step in sequence.steps.iter().rev() {
+            let mut needed = step.clone();
+
+            while !needed.is_empty() {
+                remaining = remaining.checked_sub(1)?;
+                let press = &self.presses[remaining];
+
+                if let Some(position) = needed
+                    .iter()
+                    .position(|input| !press.consumed && *input == press.input)
+                {
+                    needed.swap_remove(position);
+                    matched.push(remaining);
+                }
+            }
+        }
+
+        let last = &self.presses[*matched.first()?];
+        let first = &self.presses[*matched.last()?];
+
+        sequence
+            .window
+            .contains(first, last.frame, last.time)
+            .then_some(matched)
+    }
+
+    /// Remembers all actions pressed and direction changes made this frame, forgetting presses
+    /// which fell out of the window.
+    pub(crate) fn update(_: &mut World, resources: &mut Resources) {
+        let timer = resources.get::<FrameTimer>();
+        let (frame, time) = (timer.frame(), timer.real_elapsed());
+
+        let input = resources.get::<InputState>();
+        let input_map = resources.get::<InputMap>();
+
+        let direction = resources
+            .get::<InputBuffer>()
+            .direction_axis
+            .as_deref()
+            .and_then(|axis| {
+                let deadzone = resources.get::<InputBuffer>().direction_deadzone;
+                Direction::from_axis(input_map.axis(input, axis), deadzone)
+            });
+
+        let actions = input_map
+            .actions()
+            .filter(|action| input_map.pressed(input, action))
+            .map(|action| BufferedInput::Action(Cow::Owned(action.to_string())))
+            .collect::<Vec<_>>();
+
+        let buffer = resources.get_mut::<InputBuffer>();
+        buffer.frame = frame;
+        buffer.time = time;
+
+        while let Some(press) = buffer.presses.front()
+            && !buffer.window.contains(press, frame, time)
+        {
+            buffer.presses.pop_front();
+        }
+
+        let mut pressed = Vec::new();
+
+        if direction != buffer.direction
+            && let Some(direction) = direction
+        {
+            pressed.push(BufferedInput::Direction(direction));
+        }
+
+        buffer.direction = direction;
+        pressed.extend(actions);
+
+        buffer
+            .presses
+            .extend(pressed.into_iter().map(|input| BufferedPress {
+                input,
+                frame,
+                time,
+                consumed: false,
+            }));
+    }
+}
+
+impl Default for InputBuffer {
+    fn default() -> Self {
+        Self::new(BufferWindow::Seconds(0.5))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Remembers the given inputs as pressed on the given frame, 1/60th of a second apart.
+    fn press(buffer: &mut InputBuffer, frame: u64, inputs: &[BufferedInput]) {
+        buffer.frame = frame;
+        buffer.time = frame as f64 / 60.0;
+
+        for input in inputs {
+            buffer.presses.push_back(BufferedPress {
+                input: input.clone(),
+                frame,
+                time: buffer.time,
+                consumed: false,
+            });
+        }
+    }
+
+    /// A quarter circle forward into an attack, with forward and attack pressed in any order.
+    fn quarter_circle(window: BufferWindow) -> Sequence {
+        Sequence::new(window)
+            .then(Direction::Down)
+            .then(Direction::DownRight)
+            .then_all([Direction::Right.into(), BufferedInput::from("attack")])
+    }
+
+    #[test]
+    fn sequences_match_in_order() {
+        let mut buffer = InputBuffer::new(BufferWindow::Frames(30));
+        let sequence = quarter_circle(BufferWindow::Frames(10));
+
+        press(&mut buffer, 1, &[Direction::Down.into()]);
+        press(&mut buffer, 2, &[Direction::DownRight.into()]);
+        assert!(!buffer.sequence(&sequence));
+
+        press(&mut buffer, 3, &[Direction::Right.into(), "attack".into()]);
+        assert!(buffer.sequence(&sequence));
+
+        // the sequence only counts as entered on the frame it was completed
+        press(&mut buffer, 4, &[]);
+        assert!(!buffer.sequence(&sequence));
+        assert!(buffer.consume_sequence(&sequence));
+    }
+
+    #[test]
+    fn sequences_reject_steps_out_of_order() {
+        let mut buffer = InputBuffer::new(BufferWindow::Frames(30));
+        let sequence = quarter_circle(BufferWindow::Frames(10));
+
+        press(&mut buffer, 1, &[Direction::DownRight.into()]);
+        press(&mut buffer, 2, &[Direction::Down.into()]);
+        press(&mut buffer, 3, &[Direction::Right.into(), "attack".into()]);
+
+        assert!(!buffer.sequence(&sequence));
+    }
+
+    #[test]
+    fn combined_steps_match_in_any_order() {
+        let mut buffer = InputBuffer::new(BufferWindow::Frames(30));
+        let sequence = quarter_circle(BufferWindow::Frames(10));
+
+        press(&mut buffer, 1, &[Direction::Down.into()]);
+        press(&mut buffer, 2, &[Direction::DownRight.into()]);
+        press(&mut buffer, 3, &["attack".into()]);
+        press(&mut buffer, 4, &["block".into()]);
+        press(&mut buffer, 5, &[Direction::Right.into()]);
+
+        assert!(buffer.sequence(&sequence));
+    }
+
+    #[test]
+    fn consumed_presses_are_not_matched_again() {
+        let mut buffer = InputBuffer::new(BufferWindow::Frames(30));
+        let sequence = quarter_circle(BufferWindow::Frames(10));
+
+        press(&mut buffer, 1, &[Direction::Down.into()]);
+        press(&mut buffer, 2, &[Direction::DownRight.into()]);
+        press(&mut buffer, 3, &[Direction::Right.into(), "attack".into()]);
+
+        assert!(buffer.consume_sequence(&sequence));
+        assert!(!buffer.consume_sequence(&sequence));
+        assert!(!buffer.pressed("attack"));
+
+        // a fresh press completes the sequence again only if every step is pressed anew
+        press(&mut buffer, 4, &["attack".into()]);
+        assert!(!buffer.sequence(&sequence));
+
+        let mut buffer = InputBuffer::new(BufferWindow::Frames(30));
+        press(&mut buffer, 1, &[Direction::Down.into()]);
+        press(&mut buffer, 2, &[Direction::DownRight.into()]);
+        press(&mut buffer, 3, &[Direction::Right.into(), "attack".into()]);
+        assert!(buffer.consume("attack"));
+        assert!(!buffer.sequence(&sequence));
+    }
+
+    #[test]
+    fn sequences_expire_after_their_window() {
+        let mut buffer = InputBuffer::new(BufferWindow::Frames(30));
+        let sequence = quarter_circle(BufferWindow::Frames(5));
+
+        press(&mut buffer, 1, &[Direction::Down.into()]);
+        press(&mut buffer, 3, &[Direction::DownRight.into()]);
+        press(&mut buffer, 7, &[Direction::Right.into(), "attack".into()]);
+        assert!(!buffer.sequence(&sequence));
+
+        let mut buffer = InputBuffer::new(BufferWindow::Frames(30));
+        let sequence = quarter_circle(BufferWindow::Seconds(0.1));
+
+        press(&mut buffer, 1, &[Direction::Down.into()]);
+        press(&mut buffer, 3, &[Direction::DownRight.into()]);
+        press(&mut buffer, 6, &[Direction::Right.into(), "attack".into()]);
+        assert!(buffer.sequence(&sequence));
+    }
+}
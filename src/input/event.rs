@@ -0,0 +1,228 @@
+use glam::{UVec2, Vec2};
+use serde::{Deserialize, Serialize};
+use winit::{
+    event::{
+        DeviceEvent, ElementState, Ime, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
+    },
+    keyboard::{Key, KeyCode, ModifiersState, NamedKey, NativeKey, PhysicalKey},
+};
+
+use crate::input::{gamepad::GamepadEvent, text::ImeEvent};
+
+/// A single change in input, which every window, device and gamepad event is translated into
+/// before being applied to the [`InputState`](crate::input::InputState).
+///
+/// Unlike winit's events these can be constructed freely, so that synthetic input can be injected
+/// through the same code path as real input.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    /// A key was pressed or released.
+    Key {
+        /// The physical location of the key.
+        physical: PhysicalKey,
+        /// The key after applying the keyboard layout and modifiers.
+        logical: Key,
+        /// Whether the key was pressed or released.
+        state: ElementState,
+        /// Whether this is a repeated press from holding the key down.
+        repeat: bool,
+        /// The text produced by the key press, if any.
+        text: Option<String>,
+    },
+    /// The held modifier keys changed.
+    Modifiers(ModifiersState),
+    /// A mouse button was pressed or released.
+    MouseButton {
+        /// The mouse button which changed.
+        button: MouseButton,
+        /// Whether the button was pressed or released.
+        state: ElementState,
+    },
+    /// The cursor moved to the given position in physical pixels.
+    CursorMoved(Vec2),
+    /// The cursor left the window.
+    CursorLeft,
+    /// The scroll wheel moved by the given number of lines.
+    ScrollLines(Vec2),
+    /// The scroll wheel or touchpad moved by the given number of pixels.
+    ScrollPixels(Vec2),
+    /// The mouse moved by the given raw, unaccelerated amount.
+    MouseMotion(Vec2),
+    /// A finger touched, moved across or lifted off the screen.
+    Touch {
+        /// The identifier of the finger, unique while it touches the screen.
+        id: u64,
+        /// The stage of the touch.
+        phase: TouchPhase,
+        /// The position of the finger in physical pixels.
+        position: Vec2,
+    },
+    /// The state of a gamepad changed.
+    Gamepad(GamepadEvent),
+    /// The state of the input method editor changed.
+    Ime(ImeEvent),
+    /// The scale factor of the window changed.
+    ScaleFactorChanged(f64),
+    /// The window was resized to the given size in physical pixels.
+    Resized(UVec2),
+    /// The window lost focus, releasing everything held down.
+    FocusLost,
+}
+
+impl InputEvent {
+    /// Creates a new key press event for the given physical key, with a best guess of its logical
+    /// key on a US keyboard layout.
+    pub fn key_pressed(code: KeyCode) -> Self {
+        Self::key(code, ElementState::Pressed)
+    }
+
+    /// Creates a new key release event for the given physical key.
+    pub fn key_released(code: KeyCode) -> Self {
+        Self::key(code, ElementState::Released)
+    }
+
+    /// Creates a new key event for the given physical key, with a best guess of its logical key.
+    fn key(code: KeyCode, state: ElementState) -> Self {
+        let logical = logical_key(code);
+        let text = match (&logical, state) {
+            (Key::Character(text), ElementState::Pressed) => Some(text.to_string()),
+            (Key::Named(NamedKey::Space), ElementState::Pressed) => Some(" ".to_string()),
+            _ => None,
+        };
+
+        Self::Key {
+            physical: PhysicalKey::Code(code),
+            logical,
+            state,
+            repeat: false,
+            text,
+        }
+    }
+
+    /// Translates a [`WindowEvent`] into an [`InputEvent`], if it affects input.
+    pub(crate) fn from_window_event(event: &WindowEvent) -> Option<Self> {
+        use WindowEvent as WE;
+
+        Some(match event {
+            WE::KeyboardInput { event, .. } => Self::Key {
+                physical: event.physical_key,
+                logical: event.logical_key.clone(),
+                state: event.state,
+                repeat: event.repeat,
+                text: event.text.as_ref().map(ToString::to_string),
+            },
+            WE::ModifiersChanged(modifiers) => Self::Modifiers(modifiers.state()),
+
+            WE::MouseInput { state, button, .. } => Self::MouseButton {
+                button: *button,
+                state: *state,
+            },
+            WE::CursorMoved { position, .. } => {
+                Self::CursorMoved(Vec2::new(position.x as f32, position.y as f32))
+            }
+            WE::CursorLeft { .. } => Self::CursorLeft,
+            WE::MouseWheel { delta, .. } => match delta {
+                MouseScrollDelta::LineDelta(x, y) => Self::ScrollLines(Vec2::new(*x, *y)),
+                MouseScrollDelta::PixelDelta(delta) => {
+                    Self::ScrollPixels(Vec2::new(delta.x as f32, delta.y as f32))
+                }
+            },
+
+            WE::Touch(touch) => Self::Touch {
+                id: touch.id,
+                phase: touch.phase,
+                position: Vec2::new(touch.location.x as f32, touch.location.y as f32),
+            },
+
+            WE::Ime(ime) => Self::Ime(match ime {
+                Ime::Enabled => ImeEvent::Enabled,
+                Ime::Preedit(text, cursor) => ImeEvent::Preedit {
+                    text: text.clone(),
+                    cursor: *cursor,
+                },
+                Ime::Commit(text) => ImeEvent::Commit(text.clone()),
+                Ime::Disabled => ImeEvent::Disabled,
+            }),
+
+            WE::ScaleFactorChanged { scale_factor, .. } => Self::ScaleFactorChanged(*scale_factor),
+            WE::Focused(false) => Self::FocusLost,
+
+            _ => return None,
+        })
+    }
+
+    /// Translates a [`DeviceEvent`] into an [`InputEvent`], if it affects input.
+    pub(crate) fn from_device_event(event: &DeviceEvent) -> Option<Self> {
+        match event {
+            DeviceEvent::MouseMotion { delta: (x, y) } => {
+                Some(Self::MouseMotion(Vec2::new(*x as f32, *y as f32)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the logical key a physical key produces on a US keyboard layout without modifiers.
+fn logical_key(code: KeyCode) -> Key {
+    let character = match code {
+        KeyCode::KeyA => "a",
+        KeyCode::KeyB => "b",
+        KeyCode::KeyC => "c",
+        KeyCode::KeyD => "d",
+        KeyCode::KeyE => "e",
+        KeyCode::KeyF => "f",
+        KeyCode::KeyG => "g",
+        KeyCode::KeyH => "h",
+        KeyCode::KeyI => "i",
+        KeyCode::KeyJ => "j",
+        KeyCode::KeyK => "k",
+        KeyCode::KeyL => "l",
+        KeyCode::KeyM => "m",
+        KeyCode::KeyN => "n",
+        KeyCode::KeyO => "o",
+        KeyCode::KeyP => "p",
+        KeyCode::KeyQ => "q",
+        KeyCode::KeyR => "r",
+        KeyCode::KeyS => "s",
+        KeyCode::KeyT => "t",
+        KeyCode::KeyU => "u",
+        KeyCode::KeyV => "v",
+        KeyCode::KeyW => "w",
+        KeyCode::KeyX => "x",
+        KeyCode::KeyY => "y",
+        KeyCode::KeyZ => "z",
+        KeyCode::Digit0 => "0",
+        KeyCode::Digit1 => "1",
+        KeyCode::Digit2 => "2",
+        KeyCode::Digit3 => "3",
+        KeyCode::Digit4 => "4",
+        KeyCode::Digit5 => "5",
+        KeyCode::Digit6 => "6",
+        KeyCode::Digit7 => "7",
+        KeyCode::Digit8 => "8",
+        KeyCode::Digit9 => "9",
+        _ => {
+            let named = match code {
+                KeyCode::Space => NamedKey::Space,
+                KeyCode::Enter => NamedKey::Enter,
+                KeyCode::Escape => NamedKey::Escape,
+                KeyCode::Tab => NamedKey::Tab,
+                KeyCode::Backspace => NamedKey::Backspace,
+                KeyCode::Delete => NamedKey::Delete,
+                KeyCode::ArrowUp => NamedKey::ArrowUp,
+                KeyCode::ArrowDown => NamedKey::ArrowDown,
+                KeyCode::ArrowLeft => NamedKey::ArrowLeft,
+                KeyCode::ArrowRight => NamedKey::ArrowRight,
+                KeyCode::ShiftLeft | KeyCode::ShiftRight => NamedKey::Shift,
+                KeyCode::ControlLeft | KeyCode::ControlRight => NamedKey::Control,
+                KeyCode::AltLeft | KeyCode::AltRight => NamedKey::Alt,
+                KeyCode::SuperLeft | KeyCode::SuperRight => NamedKey::Super,
+                _ => return Key::Unidentified(NativeKey::Unidentified),
+            };
+
+            return Key::Named(named);
+        }
+    };
+
+    Key::Character(character.into())
+}
@@ -0,0 +1,377 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Identifies a single connected gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GamepadId(pub u32);
+
+/// A digital button on a gamepad, named after its position on the controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+    /// The bottom face button, i.e. A on Xbox or Cross on PlayStation controllers.
+    South,
+    /// The right face button, i.e. B on Xbox or Circle on PlayStation controllers.
+    East,
+    /// The top face button, i.e. Y on Xbox or Triangle on PlayStation controllers.
+    North,
+    /// The left face button, i.e. X on Xbox or Square on PlayStation controllers.
+    West,
+    /// The left shoulder button.
+    LeftBumper,
+    /// The right shoulder button.
+    RightBumper,
+    /// The left trigger, pressed past its actuation point.
+    LeftTrigger,
+    /// The right trigger, pressed past its actuation point.
+    RightTrigger,
+    /// The left stick, pressed in.
+    LeftStick,
+    /// The right stick, pressed in.
+    RightStick,
+    /// The select, back or share button.
+    Select,
+    /// The start, menu or options button.
+    Start,
+    /// The guide or home button.
+    Mode,
+    /// Up on the directional pad.
+    DPadUp,
+    /// Down on the directional pad.
+    DPadDown,
+    /// Left on the directional pad.
+    DPadLeft,
+    /// Right on the directional pad.
+    DPadRight,
+}
+
+/// An analog axis on a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    /// The horizontal axis of the left stick, in the range [-1, 1] with right being positive.
+    LeftStickX,
+    /// The vertical axis of the left stick, in the range [-1, 1] with up being positive.
+    LeftStickY,
+    /// The horizontal axis of the right stick, in the range [-1, 1] with right being positive.
+    RightStickX,
+    /// The vertical axis of the right stick, in the range [-1, 1] with up being positive.
+    RightStickY,
+    /// The left trigger, in the range [0, 1].
+    LeftTrigger,
+    /// The right trigger, in the range [0, 1].
+    RightTrigger,
+}
+
+/// One of the two analog sticks of a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadStick {
+    /// The left analog stick.
+    Left,
+    /// The right analog stick.
+    Right,
+}
+
+/// A change in the state of a gamepad, as reported by a [`GamepadBackend`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GamepadEvent {
+    /// A gamepad was connected.
+    Connected(GamepadId),
+    /// A gamepad was disconnected.
+    Disconnected(GamepadId),
+    /// A button on a gamepad was pressed.
+    ButtonPressed(GamepadId, GamepadButton),
+    /// A button on a gamepad was released.
+    ButtonReleased(GamepadId, GamepadButton),
+    /// An analog axis on a gamepad changed its value.
+    AxisChanged(GamepadId, GamepadAxis, f32),
+}
+
+/// A source of gamepad events, polled once at the start of every frame.
+pub trait GamepadBackend: 'static {
+    /// Appends all gamepad events which occurred since the last poll.
+    fn poll(&mut self, events: &mut Vec<GamepadEvent>);
+}
+
+/// The current state of a single connected gamepad.
+#[derive(Debug, Clone, Default)]
+pub struct Gamepad {
+    /// The buttons currently being held down.
+    buttons_held: HashSet<GamepadButton>,
+    /// The buttons pressed down this frame.
+    buttons_pressed: HashSet<GamepadButton>,
+    /// The buttons released this frame.
+    buttons_released: HashSet<GamepadButton>,
+
+    /// The raw values of all analog axes which have been reported.
+    axes: HashMap<GamepadAxis, f32>,
+    /// The radius around the center of the sticks and the start of the triggers within which
+    /// input is ignored.
+    deadzone: f32,
+}
+
+/// An in-memory [`GamepadBackend`] driven by code, for tests and synthetic input.
+///
+/// Clones share the same event queue, so a copy kept outside of the application can drive it.
+#[derive(Debug, Clone, Default)]
+pub struct VirtualGamepad {
+    /// The events queued up until the next poll, shared between all clones.
+    events: Rc<RefCell<Vec<GamepadEvent>>>,
+}
+
+impl Gamepad {
+    /// Creates a new [`Gamepad`] with nothing pressed and the given deadzone.
+    pub(crate) fn new(deadzone: f32) -> Self {
+        Self {
+            deadzone,
+            ..Default::default()
+        }
+    }
+
+    /// Flushes all button states to begin a new frame.
+    pub(crate) fn flush(&mut self) {
+        self.buttons_pressed.clear();
+        self.buttons_released.clear();
+    }
+
+    /// Handles an incoming [`GamepadEvent`] targeting this gamepad.
+    pub(crate) fn event(&mut self, event: &GamepadEvent) {
+        match *event {
+            GamepadEvent::ButtonPressed(_, button) => {
+                if self.buttons_held.insert(button) {
+                    self.buttons_pressed.insert(button);
+                }
+            }
+            GamepadEvent::ButtonReleased(_, button) => {
+                if self.buttons_held.remove(&button) {
+                    self.buttons_released.insert(button);
+                }
+            }
+            GamepadEvent::AxisChanged(_, axis, value) => {
+                self.axes.insert(axis, value);
+            }
+            GamepadEvent::Connected(_) | GamepadEvent::Disconnected(_) => {}
+        }
+    }
+
+    /// Sets the radius within which stick and trigger input is ignored.
+    pub(crate) fn set_deadzone(&mut self, deadzone: f32) {
+        self.deadzone = deadzone;
+    }
+
+    /// Returns if the given button was pressed this frame.
+    pub fn pressed(&self, button: GamepadButton) -> bool {
+        self.buttons_pressed.contains(&button)
+    }
+
+    /// Returns if the given button was released this frame.
+    pub fn released(&self, button: GamepadButton) -> bool {
+        self.buttons_released.contains(&button)
+    }
+
+    /// Returns if the given button is currently being held down.
+    pub fn held(&self, button: GamepadButton) -> bool {
+        self.buttons_held.contains(&button)
+    }
+
+    /// Returns the raw value of an analog axis, without any deadzone applied.
+    pub fn raw_axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes.get(&axis).copied().unwrap_or_default()
+    }
+
+    /// Returns the value of an analog axis, rescaled so that it starts at the edge of the
+    /// deadzone.
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        let value = self.raw_axis(axis);
+        rescale(value.abs(), self.deadzone).copysign(value)
+    }
+
+    /// Returns the position of an analog stick, with a radial deadzone applied and a length of at
+    /// most one.
+    pub fn stick(&self, stick: GamepadStick) -> Vec2 {
+        let (x, y) = match stick {
+            GamepadStick::Left => (GamepadAxis::LeftStickX, GamepadAxis::LeftStickY),
+            GamepadStick::Right => (GamepadAxis::RightStickX, GamepadAxis::RightStickY),
+        };
+
+        let raw = Vec2::new(self.raw_axis(x), self.raw_axis(y)).clamp_length_max(1.0);
+        let length = raw.length();
+
+        if length <= self.deadzone {
+            return Vec2::ZERO;
+        }
+
+        raw / length * rescale(length, self.deadzone)
+    }
+
+    /// Returns the position of the left analog stick.
+    pub fn left_stick(&self) -> Vec2 {
+        self.stick(GamepadStick::Left)
+    }
+
+    /// Returns the position of the right analog stick.
+    pub fn right_stick(&self) -> Vec2 {
+        self.stick(GamepadStick::Right)
+    }
+
+    /// Returns how far the left trigger is pulled, in the range [0, 1].
+    pub fn left_trigger(&self) -> f32 {
+        self.axis(GamepadAxis::LeftTrigger)
+    }
+
+    /// Returns how far the right trigger is pulled, in the range [0, 1].
+    pub fn right_trigger(&self) -> f32 {
+        self.axis(GamepadAxis::RightTrigger)
+    }
+}
+
+impl VirtualGamepad {
+    /// Creates a new [`VirtualGamepad`] with no queued events.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues an arbitrary gamepad event.
+    pub fn send(&self, event: GamepadEvent) {
+        self.events.borrow_mut().push(event);
+    }
+
+    /// Queues the connection of a gamepad.
+    pub fn connect(&self, id: GamepadId) {
+        self.send(GamepadEvent::Connected(id));
+    }
+
+    /// Queues the disconnection of a gamepad.
+    pub fn disconnect(&self, id: GamepadId) {
+        self.send(GamepadEvent::Disconnected(id));
+    }
+
+    /// Queues a button press on a gamepad.
+    pub fn press(&self, id: GamepadId, button: GamepadButton) {
+        self.send(GamepadEvent::ButtonPressed(id, button));
+    }
+
+    /// Queues a button release on a gamepad.
+    pub fn release(&self, id: GamepadId, button: GamepadButton) {
+        self.send(GamepadEvent::ButtonReleased(id, button));
+    }
+
+    /// Queues a change of an analog axis on a gamepad.
+    pub fn set_axis(&self, id: GamepadId, axis: GamepadAxis, value: f32) {
+        self.send(GamepadEvent::AxisChanged(id, axis, value));
+    }
+}
+
+impl GamepadBackend for VirtualGamepad {
+    fn poll(&mut self, events: &mut Vec<GamepadEvent>) {
+        events.append(&mut self.events.borrow_mut());
+    }
+}
+
+/// Rescales a value in the range [deadzone, 1] into the range [0, 1], clamping anything below.
+fn rescale(value: f32, deadzone: f32) -> f32 {
+    if value <= deadzone {
+        0.0
+    } else {
+        ((value - deadzone) / (1.0 - deadzone).max(f32::EPSILON)).min(1.0)
+    }
+}
+
+#[cfg(feature = "gilrs")]
+pub use gilrs_backend::GilrsBackend;
+
+#[cfg(feature = "gilrs")]
+mod gilrs_backend {
+    use gilrs::{Axis, Button, EventType, Gilrs};
+
+    use super::{GamepadAxis, GamepadBackend, GamepadButton, GamepadEvent, GamepadId};
+
+    /// A [`GamepadBackend`] reading physical controllers through [`gilrs`].
+    pub struct GilrsBackend {
+        /// The gilrs context all events are read from.
+        gilrs: Gilrs,
+    }
+
+    impl GilrsBackend {
+        /// Creates a new [`GilrsBackend`], reporting all already connected gamepads on the first
+        /// poll.
+        pub fn new() -> anyhow::Result<Self> {
+            let gilrs =
+                Gilrs::new().map_err(|e| anyhow::anyhow!("while initializing gilrs: {e}"))?;
+            Ok(Self { gilrs })
+        }
+    }
+
+    impl GamepadBackend for GilrsBackend {
+        fn poll(&mut self, events: &mut Vec<GamepadEvent>) {
+            while let Some(gilrs::Event { id, event, .. }) = self.gilrs.next_event() {
+                let id = GamepadId(usize::from(id) as u32);
+
+                let event = match event {
+                    EventType::Connected => GamepadEvent::Connected(id),
+                    EventType::Disconnected => GamepadEvent::Disconnected(id),
+                    EventType::ButtonPressed(button, _) => match map_button(button) {
+                        Some(button) => GamepadEvent::ButtonPressed(id, button),
+                        None => continue,
+                    },
+                    EventType::ButtonReleased(button, _) => match map_button(button) {
+                        Some(button) => GamepadEvent::ButtonReleased(id, button),
+                        None => continue,
+                    },
+                    EventType::ButtonChanged(Button::LeftTrigger2, value, _) => {
+                        GamepadEvent::AxisChanged(id, GamepadAxis::LeftTrigger, value)
+                    }
+                    EventType::ButtonChanged(Button::RightTrigger2, value, _) => {
+                        GamepadEvent::AxisChanged(id, GamepadAxis::RightTrigger, value)
+                    }
+                    EventType::AxisChanged(axis, value, _) => match map_axis(axis) {
+                        Some(axis) => GamepadEvent::AxisChanged(id, axis, value),
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+
+                events.push(event);
+            }
+        }
+    }
+
+    /// Maps a gilrs button onto the engine's button, if it has an equivalent.
+    fn map_button(button: Button) -> Option<GamepadButton> {
+        Some(match button {
+            Button::South => GamepadButton::South,
+            Button::East => GamepadButton::East,
+            Button::North => GamepadButton::North,
+            Button::West => GamepadButton::West,
+            Button::LeftTrigger => GamepadButton::LeftBumper,
+            Button::RightTrigger => GamepadButton::RightBumper,
+            Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+            Button::RightTrigger2 => GamepadButton::RightTrigger,
+            Button::LeftThumb => GamepadButton::LeftStick,
+            Button::RightThumb => GamepadButton::RightStick,
+            Button::Select => GamepadButton::Select,
+            Button::Start => GamepadButton::Start,
+            Button::Mode => GamepadButton::Mode,
+            Button::DPadUp => GamepadButton::DPadUp,
+            Button::DPadDown => GamepadButton::DPadDown,
+            Button::DPadLeft => GamepadButton::DPadLeft,
+            Button::DPadRight => GamepadButton::DPadRight,
+            _ => return None,
+        })
+    }
+
+    /// Maps a gilrs axis onto the engine's axis, if it has an equivalent.
+    fn map_axis(axis: Axis) -> Option<GamepadAxis> {
+        Some(match axis {
+            Axis::LeftStickX => GamepadAxis::LeftStickX,
+            Axis::LeftStickY => GamepadAxis::LeftStickY,
+            Axis::RightStickX => GamepadAxis::RightStickX,
+            Axis::RightStickY => GamepadAxis::RightStickY,
+            _ => return None,
+        })
+    }
+}
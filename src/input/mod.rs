@@ -0,0 +1,651 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use glam::Vec2;
+use winit::{
+    event::{ElementState, MouseButton, TouchPhase},
+    keyboard::{Key, KeyCode, ModifiersState, PhysicalKey},
+};
+
+use crate::input::{
+    event::InputEvent,
+    gamepad::{Gamepad, GamepadBackend, GamepadButton, GamepadEvent, GamepadId},
+    touch::TouchState,
+};
+
+pub mod actions;
+pub mod buffer;
+pub mod event;
+pub mod gamepad;
+pub mod replay;
+pub mod text;
+pub mod touch;
+
+/// A key combined with an exact set of modifiers, such as `Ctrl+S`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chord {
+    /// The modifiers which must be held, and no others.
+    pub modifiers: ModifiersState,
+    /// The key which completes the chord.
+    pub key: KeyCode,
+}
+
+/// Manages the current input state of the window.
+pub struct InputState {
+    /// The keys currently being held down.
+    keys_held: HashSet<KeyCode>,
+
+    /// The keys currently pressed down this frame.
+    keys_pressed: HashSet<KeyCode>,
+    /// The keys currently released this frame.
+    keys_released: HashSet<KeyCode>,
+
+    /// The logical keys currently being held down, keyed by the physical key producing them.
+    logical_held: HashMap<PhysicalKey, Key>,
+    /// The logical keys pressed down this frame.
+    logical_pressed: HashSet<Key>,
+    /// The logical keys released this frame.
+    logical_released: HashSet<Key>,
+    /// The modifier keys currently being held down.
+    modifiers: ModifiersState,
+
+    /// The mouse buttons currently being held down.
+    buttons_held: HashSet<MouseButton>,
+    /// The mouse buttons pressed down this frame.
+    buttons_pressed: HashSet<MouseButton>,
+    /// The mouse buttons released this frame.
+    buttons_released: HashSet<MouseButton>,
+
+    /// The position of the cursor in physical pixels, if it is inside the window.
+    cursor: Option<Vec2>,
+    /// How far the cursor moved this frame in physical pixels.
+    cursor_delta: Vec2,
+    /// The scale factor of the window, used to convert into logical pixels.
+    scale_factor: f64,
+
+    /// How far the scroll wheel moved this frame in lines.
+    scroll_lines: Vec2,
+    /// How far the scroll wheel or touchpad moved this frame in pixels.
+    scroll_pixels: Vec2,
+
+    /// The raw, unaccelerated mouse motion reported by the device this frame.
+    mouse_motion: Vec2,
+
+    /// All currently connected gamepads.
+    gamepads: BTreeMap<GamepadId, Gamepad>,
+    /// The gamepad events which occurred this frame.
+    gamepad_events: Vec<GamepadEvent>,
+    /// The deadzone applied to the sticks and triggers of every gamepad.
+    gamepad_deadzone: f32,
+    /// The source gamepad events are polled from, if any.
+    gamepad_backend: Option<Box<dyn GamepadBackend>>,
+
+    /// All fingers touching the screen along with the gestures they made.
+    touch: TouchState,
+    /// Whether the first finger touching the screen moves the cursor and presses the left mouse
+    /// button.
+    touch_mouse_emulation: bool,
+    /// The finger currently emulating the mouse, if any.
+    emulating_touch: Option<u64>,
+}
+
+impl InputState {
+    /// The deadzone applied to gamepad sticks and triggers by default.
+    pub const DEFAULT_GAMEPAD_DEADZONE: f32 = 0.15;
+
+    /// Creates a new [`InputState`] manager.
+    pub(crate) fn new() -> Self {
+        Self {
+            keys_held: HashSet::new(),
+            keys_pressed: HashSet::new(),
+            keys_released: HashSet::new(),
+            logical_held: HashMap::new(),
+            logical_pressed: HashSet::new(),
+            logical_released: HashSet::new(),
+            modifiers: ModifiersState::empty(),
+            buttons_held: HashSet::new(),
+            buttons_pressed: HashSet::new(),
+            buttons_released: HashSet::new(),
+            cursor: None,
+            cursor_delta: Vec2::ZERO,
+            scale_factor: 1.0,
+            scroll_lines: Vec2::ZERO,
+            scroll_pixels: Vec2::ZERO,
+            mouse_motion: Vec2::ZERO,
+            gamepads: BTreeMap::new(),
+            gamepad_events: Vec::new(),
+            gamepad_deadzone: Self::DEFAULT_GAMEPAD_DEADZONE,
+            gamepad_backend: None,
+            touch: TouchState::default(),
+            touch_mouse_emulation: false,
+            emulating_touch: None,
+        }
+    }
+
+    /// Flushes all per-frame input state to begin a new frame.
+    ///
+    /// This is called by the application at the end of every frame, and only needs to be called
+    /// manually when driving an [`InputState`] without one, such as in tests.
+    pub fn flush(&mut self) {
+        self.keys_pressed.drain();
+        self.keys_released.drain();
+        self.logical_pressed.drain();
+        self.logical_released.drain();
+        self.buttons_pressed.drain();
+        self.buttons_released.drain();
+
+        self.cursor_delta = Vec2::ZERO;
+        self.scroll_lines = Vec2::ZERO;
+        self.scroll_pixels = Vec2::ZERO;
+        self.mouse_motion = Vec2::ZERO;
+
+        self.gamepad_events.clear();
+        self.gamepads.values_mut().for_each(Gamepad::flush);
+
+        self.touch.flush();
+    }
+
+    /// Recognizes the touch gestures made since the start of the frame, given the current real
+    /// time in seconds.
+    ///
+    /// This is called by the application at the start of every frame, and only needs to be
+    /// called manually when driving an [`InputState`] without one, such as in tests.
+    pub fn update_touches(&mut self, time: f64) {
+        self.touch.update(time);
+    }
+
+    /// Sets the source gamepad events are polled from.
+    pub(crate) fn set_gamepad_backend(&mut self, backend: Box<dyn GamepadBackend>) {
+        self.gamepad_backend = Some(backend);
+    }
+
+    /// Polls the gamepad backend for all events since the last frame, to be applied through
+    /// [`InputState::inject`].
+    pub(crate) fn poll_gamepads(&mut self) -> Vec<GamepadEvent> {
+        let mut events = Vec::new();
+
+        if let Some(backend) = &mut self.gamepad_backend {
+            backend.poll(&mut events);
+        }
+
+        events
+    }
+
+    /// Handles an incoming [`GamepadEvent`].
+    fn gamepad_event(&mut self, event: &GamepadEvent) {
+        match *event {
+            GamepadEvent::Connected(id) => {
+                self.gamepads
+                    .entry(id)
+                    .or_insert_with(|| Gamepad::new(self.gamepad_deadzone));
+            }
+            GamepadEvent::Disconnected(id) => {
+                self.gamepads.remove(&id);
+            }
+            GamepadEvent::ButtonPressed(id, _)
+            | GamepadEvent::ButtonReleased(id, _)
+            | GamepadEvent::AxisChanged(id, _, _) => {
+                // backends may report input before the connection itself
+                self.gamepads
+                    .entry(id)
+                    .or_insert_with(|| Gamepad::new(self.gamepad_deadzone))
+                    .event(event);
+            }
+        }
+
+        self.gamepad_events.push(*event);
+    }
+
+    /// Sets the scale factor of the window.
+    pub(crate) fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+    }
+
+    /// Applies a single [`InputEvent`], whether it came from the window or was injected.
+    ///
+    /// This only updates the input state, while
+    /// [`Headless::inject`](crate::application::Headless::inject) also resizes the viewport and
+    /// sends the resulting [`TextInput`](text::TextInput) and [`ImeEvent`](text::ImeEvent) events,
+    /// like window input does.
+    pub fn inject(&mut self, event: &InputEvent) {
+        match event {
+            InputEvent::Key {
+                physical,
+                logical,
+                state,
+                repeat,
+                ..
+            } => self.keyboard_event(*physical, logical, *state, *repeat),
+            InputEvent::Modifiers(modifiers) => self.modifiers = *modifiers,
+
+            InputEvent::MouseButton { button, state } => self.mouse_button_event(*state, *button),
+            InputEvent::CursorMoved(position) => self.cursor_moved(*position),
+            InputEvent::CursorLeft => self.cursor = None,
+            InputEvent::ScrollLines(lines) => self.scroll_lines += *lines,
+            InputEvent::ScrollPixels(pixels) => self.scroll_pixels += *pixels,
+            InputEvent::MouseMotion(motion) => self.mouse_motion += *motion,
+
+            InputEvent::Touch {
+                id,
+                phase,
+                position,
+            } => self.touch_event(*id, *phase, *position),
+
+            InputEvent::Gamepad(event) => self.gamepad_event(event),
+
+            InputEvent::ScaleFactorChanged(scale_factor) => self.scale_factor = *scale_factor,
+
+            // key releases are not delivered while unfocused, so keys would otherwise get stuck
+            InputEvent::FocusLost => self.release_all(),
+
+            InputEvent::Ime(_) | InputEvent::Resized(_) => {}
+        }
+    }
+
+    /// Injects a synthetic press of the given key.
+    pub fn press_key(&mut self, code: KeyCode) {
+        self.inject(&InputEvent::key_pressed(code));
+    }
+
+    /// Injects a synthetic release of the given key.
+    pub fn release_key(&mut self, code: KeyCode) {
+        self.inject(&InputEvent::key_released(code));
+    }
+
+    /// Injects a synthetic press of the given mouse button.
+    pub fn press_mouse(&mut self, button: MouseButton) {
+        self.inject(&InputEvent::MouseButton {
+            button,
+            state: ElementState::Pressed,
+        });
+    }
+
+    /// Injects a synthetic release of the given mouse button.
+    pub fn release_mouse(&mut self, button: MouseButton) {
+        self.inject(&InputEvent::MouseButton {
+            button,
+            state: ElementState::Released,
+        });
+    }
+
+    /// Injects a synthetic movement of the cursor to the given position in physical pixels.
+    pub fn move_cursor(&mut self, position: Vec2) {
+        self.inject(&InputEvent::CursorMoved(position));
+    }
+
+    /// Injects a synthetic touch of the given finger at a position in physical pixels.
+    pub fn touch_at(&mut self, id: u64, phase: TouchPhase, position: Vec2) {
+        self.inject(&InputEvent::Touch {
+            id,
+            phase,
+            position,
+        });
+    }
+
+    /// Handles an incoming key event.
+    fn keyboard_event(
+        &mut self,
+        physical: PhysicalKey,
+        logical: &Key,
+        state: ElementState,
+        repeat: bool,
+    ) {
+        match state {
+            ElementState::Pressed if !repeat => {
+                self.logical_held.insert(physical, logical.clone());
+                self.logical_pressed.insert(logical.clone());
+            }
+            ElementState::Released => {
+                // the logical key may have changed with the modifiers since it was pressed
+                let key = self
+                    .logical_held
+                    .remove(&physical)
+                    .unwrap_or_else(|| logical.clone());
+                self.logical_released.insert(key);
+            }
+            _ => {}
+        }
+
+        let PhysicalKey::Code(code) = physical else {
+            return;
+        };
+
+        match state {
+            ElementState::Pressed if !repeat => {
+                self.keys_held.insert(code);
+                self.keys_pressed.insert(code);
+            }
+            ElementState::Released => {
+                self.keys_released.insert(code);
+                self.keys_held.remove(&code);
+            }
+            _ => {}
+        }
+    }
+
+    /// Handles the cursor moving to a new position in physical pixels.
+    fn cursor_moved(&mut self, position: Vec2) {
+        if let Some(last) = self.cursor {
+            self.cursor_delta += position - last;
+        }
+
+        self.cursor = Some(position);
+    }
+
+    /// Handles an incoming touch event, emulating the mouse with the first finger if enabled.
+    fn touch_event(&mut self, id: u64, phase: TouchPhase, position: Vec2) {
+        self.touch.event(id, phase, position);
+
+        if !self.touch_mouse_emulation {
+            return;
+        }
+
+        match phase {
+            TouchPhase::Started if self.emulating_touch.is_none() => {
+                self.emulating_touch = Some(id);
+                self.cursor = Some(position);
+                self.mouse_button_event(ElementState::Pressed, MouseButton::Left);
+            }
+            TouchPhase::Moved if self.emulating_touch == Some(id) => self.cursor_moved(position),
+            TouchPhase::Ended | TouchPhase::Cancelled if self.emulating_touch == Some(id) => {
+                self.emulating_touch = None;
+                self.cursor = Some(position);
+                self.mouse_button_event(ElementState::Released, MouseButton::Left);
+            }
+            _ => {}
+        }
+    }
+
+    /// Releases all held keys and mouse buttons, e.g. when the window loses focus.
+    fn release_all(&mut self) {
+        self.keys_released.extend(self.keys_held.drain());
+        self.logical_released
+            .extend(self.logical_held.drain().map(|(_, key)| key));
+        self.buttons_released.extend(self.buttons_held.drain());
+        self.modifiers = ModifiersState::empty();
+    }
+
+    /// Handles an incoming mouse button event.
+    fn mouse_button_event(&mut self, state: ElementState, button: MouseButton) {
+        match state {
+            ElementState::Pressed => {
+                self.buttons_held.insert(button);
+                self.buttons_pressed.insert(button);
+            }
+            ElementState::Released => {
+                self.buttons_released.insert(button);
+                self.buttons_held.remove(&button);
+            }
+        }
+    }
+
+    /// Returns if the given key code was pressed this frame.
+    pub fn key_pressed(&self, code: KeyCode) -> bool {
+        self.keys_pressed.contains(&code)
+    }
+
+    /// Returns if the given key code was released this frame.
+    pub fn key_released(&self, code: KeyCode) -> bool {
+        self.keys_released.contains(&code)
+    }
+
+    /// Returns if the given key code is currently being held down.
+    pub fn key_held(&self, code: KeyCode) -> bool {
+        self.keys_held.contains(&code)
+    }
+
+    /// Returns if the given logical key, i.e. a character or named key after applying the keyboard
+    /// layout, was pressed this frame.
+    pub fn logical_key_pressed(&self, key: &Key) -> bool {
+        self.logical_pressed.contains(key)
+    }
+
+    /// Returns if the given logical key was released this frame.
+    pub fn logical_key_released(&self, key: &Key) -> bool {
+        self.logical_released.contains(key)
+    }
+
+    /// Returns if the given logical key is currently being held down.
+    pub fn logical_key_held(&self, key: &Key) -> bool {
+        self.logical_held.values().any(|held| held == key)
+    }
+
+    /// Returns if the given character was typed this frame, ignoring case.
+    pub fn char_pressed(&self, c: char) -> bool {
+        self.logical_pressed.iter().any(|key| key_is_char(key, c))
+    }
+
+    /// Returns if a key producing the given character is currently being held down, ignoring case.
+    pub fn char_held(&self, c: char) -> bool {
+        self.logical_held.values().any(|key| key_is_char(key, c))
+    }
+
+    /// Returns the modifier keys currently being held down.
+    pub fn modifiers(&self) -> ModifiersState {
+        self.modifiers
+    }
+
+    /// Returns if either shift key is currently being held down.
+    pub fn shift(&self) -> bool {
+        self.modifiers.shift_key()
+    }
+
+    /// Returns if either control key is currently being held down.
+    pub fn ctrl(&self) -> bool {
+        self.modifiers.control_key()
+    }
+
+    /// Returns if either alt key is currently being held down.
+    pub fn alt(&self) -> bool {
+        self.modifiers.alt_key()
+    }
+
+    /// Returns if either super key, i.e. the Windows or Command key, is currently being held down.
+    pub fn super_key(&self) -> bool {
+        self.modifiers.super_key()
+    }
+
+    /// Returns if the key of the chord was pressed this frame while holding exactly its modifiers.
+    pub fn chord_pressed(&self, chord: Chord) -> bool {
+        self.key_pressed(chord.key) && self.modifiers == chord.modifiers
+    }
+
+    /// Returns if the key of the chord is held down along with exactly its modifiers.
+    pub fn chord_held(&self, chord: Chord) -> bool {
+        self.key_held(chord.key) && self.modifiers == chord.modifiers
+    }
+
+    /// Returns if the given mouse button was pressed this frame.
+    pub fn mouse_pressed(&self, button: MouseButton) -> bool {
+        self.buttons_pressed.contains(&button)
+    }
+
+    /// Returns if the given mouse button was released this frame.
+    pub fn mouse_released(&self, button: MouseButton) -> bool {
+        self.buttons_released.contains(&button)
+    }
+
+    /// Returns if the given mouse button is currently being held down.
+    pub fn mouse_held(&self, button: MouseButton) -> bool {
+        self.buttons_held.contains(&button)
+    }
+
+    /// Returns the position of the cursor in physical pixels, relative to the top left corner of
+    /// the window, if it is inside the window.
+    pub fn cursor_position(&self) -> Option<Vec2> {
+        self.cursor
+    }
+
+    /// Returns the position of the cursor in logical pixels, relative to the top left corner of
+    /// the window, if it is inside the window.
+    pub fn cursor_position_logical(&self) -> Option<Vec2> {
+        self.cursor.map(|cursor| cursor / self.scale_factor as f32)
+    }
+
+    /// Returns how far the cursor moved this frame in physical pixels.
+    pub fn cursor_delta(&self) -> Vec2 {
+        self.cursor_delta
+    }
+
+    /// Returns how far the cursor moved this frame in logical pixels.
+    pub fn cursor_delta_logical(&self) -> Vec2 {
+        self.cursor_delta / self.scale_factor as f32
+    }
+
+    /// Returns the scale factor of the window, converting logical into physical pixels.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Returns how far the scroll wheel moved this frame in lines.
+    pub fn scroll_lines(&self) -> Vec2 {
+        self.scroll_lines
+    }
+
+    /// Returns how far the scroll wheel or touchpad moved this frame in pixels.
+    pub fn scroll_pixels(&self) -> Vec2 {
+        self.scroll_pixels
+    }
+
+    /// Returns the raw mouse motion reported by the device this frame, unaffected by cursor
+    /// acceleration or the window's bounds, suitable for mouse-look.
+    pub fn mouse_motion(&self) -> Vec2 {
+        self.mouse_motion
+    }
+
+    /// Returns an iterator over all currently connected gamepads.
+    pub fn gamepads(&self) -> impl Iterator<Item = (GamepadId, &Gamepad)> {
+        self.gamepads.iter().map(|(id, gamepad)| (*id, gamepad))
+    }
+
+    /// Returns the state of the given gamepad, if it is connected.
+    pub fn gamepad(&self, id: GamepadId) -> Option<&Gamepad> {
+        self.gamepads.get(&id)
+    }
+
+    /// Returns all gamepad events which occurred this frame, including connections and
+    /// disconnections.
+    pub fn gamepad_events(&self) -> &[GamepadEvent] {
+        &self.gamepad_events
+    }
+
+    /// Returns if the given button was pressed this frame on any gamepad.
+    pub fn gamepad_pressed(&self, button: GamepadButton) -> bool {
+        self.gamepads
+            .values()
+            .any(|gamepad| gamepad.pressed(button))
+    }
+
+    /// Returns if the given button was released this frame on any gamepad.
+    pub fn gamepad_released(&self, button: GamepadButton) -> bool {
+        self.gamepads
+            .values()
+            .any(|gamepad| gamepad.released(button))
+    }
+
+    /// Returns if the given button is currently being held down on any gamepad.
+    pub fn gamepad_held(&self, button: GamepadButton) -> bool {
+        self.gamepads.values().any(|gamepad| gamepad.held(button))
+    }
+
+    /// Returns the deadzone applied to the sticks and triggers of every gamepad.
+    pub fn gamepad_deadzone(&self) -> f32 {
+        self.gamepad_deadzone
+    }
+
+    /// Sets the deadzone applied to the sticks and triggers of every gamepad.
+    pub fn set_gamepad_deadzone(&mut self, deadzone: f32) {
+        self.gamepad_deadzone = deadzone.clamp(0.0, 1.0);
+
+        for gamepad in self.gamepads.values_mut() {
+            gamepad.set_deadzone(self.gamepad_deadzone);
+        }
+    }
+
+    /// Returns all fingers touching the screen along with the gestures they made.
+    pub fn touch(&self) -> &TouchState {
+        &self.touch
+    }
+
+    /// Returns a mutable reference to the touch state, e.g. to change the gesture settings.
+    pub fn touch_mut(&mut self) -> &mut TouchState {
+        &mut self.touch
+    }
+
+    /// Returns if the first finger touching the screen emulates the mouse.
+    pub fn touch_mouse_emulation(&self) -> bool {
+        self.touch_mouse_emulation
+    }
+
+    /// Sets if the first finger touching the screen moves the cursor and presses the left mouse
+    /// button.
+    pub fn set_touch_mouse_emulation(&mut self, enabled: bool) {
+        self.touch_mouse_emulation = enabled;
+    }
+}
+
+impl Chord {
+    /// Creates a new [`Chord`] of a key without any modifiers.
+    pub fn new(key: KeyCode) -> Self {
+        Self {
+            modifiers: ModifiersState::empty(),
+            key,
+        }
+    }
+
+    /// Creates a new [`Chord`] of a key with the control key, e.g. `Ctrl+S`.
+    pub fn ctrl(key: KeyCode) -> Self {
+        Self::new(key).with_ctrl()
+    }
+
+    /// Creates a new [`Chord`] of a key with the platform's primary modifier, i.e. the Command key
+    /// on macOS and the control key everywhere else.
+    pub fn primary(key: KeyCode) -> Self {
+        if cfg!(target_os = "macos") {
+            Self::new(key).with_super()
+        } else {
+            Self::new(key).with_ctrl()
+        }
+    }
+
+    /// Adds the shift key to the chord.
+    pub fn with_shift(mut self) -> Self {
+        self.modifiers |= ModifiersState::SHIFT;
+        self
+    }
+
+    /// Adds the control key to the chord.
+    pub fn with_ctrl(mut self) -> Self {
+        self.modifiers |= ModifiersState::CONTROL;
+        self
+    }
+
+    /// Adds the alt key to the chord.
+    pub fn with_alt(mut self) -> Self {
+        self.modifiers |= ModifiersState::ALT;
+        self
+    }
+
+    /// Adds the super key to the chord.
+    pub fn with_super(mut self) -> Self {
+        self.modifiers |= ModifiersState::SUPER;
+        self
+    }
+}
+
+/// Returns if the logical key produces the given character, ignoring case.
+fn key_is_char(key: &Key, c: char) -> bool {
+    let Key::Character(text) = key else {
+        return false;
+    };
+
+    let mut chars = text.chars();
+    chars
+        .next()
+        .is_some_and(|first| chars.next().is_none() && first.to_lowercase().eq(c.to_lowercase()))
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
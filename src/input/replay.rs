@@ -0,0 +1,318 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
+use glam::UVec2;
+use serde::{Deserialize, Serialize};
+use web_time::Duration;
+use winit::dpi::PhysicalSize;
+
+use crate::{
+    clock::ManualClock,
+    ecs::{Resources, World},
+    input::event::InputEvent,
+    reflect::TypeRegistry,
+};
+
+/// A recording of all input events along with the duration of every frame, which can be played
+/// back to deterministically reproduce a session.
+///
+/// The first frame starts with a [`Resized`](InputEvent::Resized) and
+/// [`ScaleFactorChanged`](InputEvent::ScaleFactorChanged) event holding the initial window
+/// state, so the viewport matches the recording regardless of the window it is replayed in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    /// The version of the replay format, used to reject incompatible files.
+    version: u32,
+    /// All recorded frames, in order.
+    pub frames: Vec<ReplayFrame>,
+}
+
+/// The input of a single recorded frame.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    /// The real time the frame took.
+    pub delta: Duration,
+    /// The input events applied at the start of the frame, in order.
+    pub events: Vec<InputEvent>,
+    /// The checksum of the world after the frame's update, if recorded.
+    pub checksum: Option<u64>,
+}
+
+/// The settings for recording or replaying input, inserted by
+/// [`with_input_recording`](crate::application::ApplicationBuilder::with_input_recording) or
+/// [`with_input_replay`](crate::application::ApplicationBuilder::with_input_replay).
+#[derive(Debug, Clone)]
+pub enum ReplaySettings {
+    /// Records all input into a replay file, written when the window is closed or a save is
+    /// requested.
+    Record {
+        /// The path the replay is written to.
+        path: PathBuf,
+        /// Whether to record a checksum of the world every frame.
+        checksums: bool,
+    },
+    /// Plays back a replay file, ignoring all real input until it finishes.
+    Replay {
+        /// The path the replay is read from.
+        path: PathBuf,
+    },
+}
+
+/// The progress of the replay being played back, if any.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayStatus {
+    /// The index of the next frame to play back.
+    pub frame: usize,
+    /// The total number of frames in the replay.
+    pub frames: usize,
+    /// The first frame whose world checksum didn't match the recording, if any.
+    pub desynced_at: Option<usize>,
+    /// Set to write the recording so far to disk at the end of the frame, e.g. before doing
+    /// something likely to crash.
+    pub save_requested: bool,
+}
+
+/// An active recording or playback of a [`Replay`].
+pub(crate) enum ReplaySession {
+    /// All input is being recorded.
+    Recording {
+        /// The path the replay is written to.
+        path: PathBuf,
+        /// Whether to record a checksum of the world every frame.
+        checksums: bool,
+        /// The frames recorded so far.
+        replay: Replay,
+        /// The events received since the last frame was recorded.
+        pending: Vec<InputEvent>,
+        /// The number of frames already written to disk.
+        saved: usize,
+    },
+    /// A replay is being played back.
+    Replaying {
+        /// The replay being played back.
+        replay: Replay,
+        /// The clock driving the frame timer with the recorded frame durations.
+        clock: ManualClock,
+    },
+}
+
+impl Replay {
+    /// The current version of the replay format.
+    const VERSION: u32 = 2;
+
+    /// Creates a new, empty [`Replay`].
+    pub fn new() -> Self {
+        Self {
+            version: Self::VERSION,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Reads a [`Replay`] from the compact binary file at the given path.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("while opening replay file {}", path.display()))?;
+
+        let replay: Self = bincode::deserialize_from(BufReader::new(file))
+            .with_context(|| format!("while parsing replay file {}", path.display()))?;
+
+        if replay.version != Self::VERSION {
+            bail!(
+                "replay file {} has version {}, expected {}",
+                path.display(),
+                replay.version,
+                Self::VERSION
+            );
+        }
+
+        Ok(replay)
+    }
+
+    /// Writes this [`Replay`] to a compact binary file at the given path.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("while creating replay file {}", path.display()))?;
+
+        bincode::serialize_into(BufWriter::new(file), self)
+            .with_context(|| format!("while writing replay file {}", path.display()))
+    }
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReplaySession {
+    /// Starts recording or replaying as described by the settings, recording the initial size
+    /// and scale factor of the window.
+    pub fn new(
+        settings: &ReplaySettings,
+        size: PhysicalSize<u32>,
+        scale_factor: f64,
+    ) -> anyhow::Result<Self> {
+        Ok(match settings {
+            ReplaySettings::Record { path, checksums } => Self::Recording {
+                path: path.clone(),
+                checksums: *checksums,
+                replay: Replay::new(),
+                pending: vec![
+                    InputEvent::Resized(UVec2::new(size.width, size.height)),
+                    InputEvent::ScaleFactorChanged(scale_factor),
+                ],
+                saved: 0,
+            },
+            ReplaySettings::Replay { path } => Self::Replaying {
+                replay: Replay::load(path)?,
+                clock: ManualClock::new(),
+            },
+        })
+    }
+
+    /// Returns the clock which must drive the frame timer, if replaying.
+    pub fn clock(&self) -> Option<&ManualClock> {
+        match self {
+            Self::Recording { .. } => None,
+            Self::Replaying { clock, .. } => Some(clock),
+        }
+    }
+
+    /// Returns if real input should be applied, which is only the case while not replaying.
+    pub fn accepts_input(&self, status: &ReplayStatus) -> bool {
+        match self {
+            Self::Recording { .. } => true,
+            Self::Replaying { replay, .. } => status.frame >= replay.frames.len(),
+        }
+    }
+
+    /// Records an input event applied during the current frame.
+    pub fn record(&mut self, event: &InputEvent) {
+        if let Self::Recording { pending, .. } = self {
+            pending.push(event.clone());
+        }
+    }
+
+    /// Starts the next frame, returning the recorded events to apply if replaying.
+    ///
+    /// This must be called before the frame timer is ticked, so that it advances by the recorded
+    /// frame duration.
+    pub fn begin_frame(&mut self, status: &mut ReplayStatus) -> Vec<InputEvent> {
+        let Self::Replaying { replay, clock } = self else {
+            return Vec::new();
+        };
+
+        status.frames = replay.frames.len();
+
+        let Some(frame) = replay.frames.get(status.frame) else {
+            return Vec::new();
+        };
+
+        clock.advance(frame.delta);
+
+        if status.frame + 1 == replay.frames.len() {
+            log::info!("replay finished after {} frames", replay.frames.len());
+        }
+
+        frame.events.clone()
+    }
+
+    /// Ends the current frame, recording its duration and checksum or verifying the checksum
+    /// against the recording.
+    pub fn end_frame(
+        &mut self,
+        delta: Duration,
+        world: &World,
+        resources: &Resources,
+        status: &mut ReplayStatus,
+    ) {
+        match self {
+            Self::Recording {
+                checksums,
+                replay,
+                pending,
+                ..
+            } => {
+                replay.frames.push(ReplayFrame {
+                    delta,
+                    events: std::mem::take(pending),
+                    checksum: checksums.then(|| checksum(world, resources)).flatten(),
+                });
+
+                status.frame = replay.frames.len();
+                status.frames = replay.frames.len();
+            }
+            Self::Replaying { replay, .. } => {
+                let Some(frame) = replay.frames.get(status.frame) else {
+                    return;
+                };
+
+                if let Some(expected) = frame.checksum
+                    && status.desynced_at.is_none()
+                    && checksum(world, resources) != Some(expected)
+                {
+                    log::error!("replay desynced at frame {}", status.frame);
+                    status.desynced_at = Some(status.frame);
+                }
+
+                status.frame += 1;
+            }
+        }
+    }
+
+    /// Writes all frames recorded so far to disk, if recording.
+    pub fn save(&mut self) {
+        let Self::Recording {
+            path,
+            replay,
+            saved,
+            ..
+        } = self
+        else {
+            return;
+        };
+
+        match replay.save(&*path) {
+            Ok(()) => {
+                log::info!("wrote replay to {}", path.display());
+                *saved = replay.frames.len();
+            }
+            Err(e) => log::error!("failed to write replay: {e:#}"),
+        }
+    }
+}
+
+impl Drop for ReplaySession {
+    fn drop(&mut self) {
+        // the window may be gone without being closed, e.g. when exiting through a panic
+        if let Self::Recording { replay, saved, .. } = self
+            && replay.frames.len() > *saved
+        {
+            self.save();
+        }
+    }
+}
+
+/// Computes a checksum of all serializable components in the world, as registered in the
+/// [`TypeRegistry`].
+fn checksum(world: &World, resources: &Resources) -> Option<u64> {
+    let scene = resources
+        .get::<TypeRegistry>()
+        .save_world(world)
+        .and_then(|scene| scene.to_json())
+        .inspect_err(|e| log::error!("failed to compute world checksum: {e:#}"))
+        .ok()?;
+
+    // FNV-1a, as its output is stable across platforms and compiler versions
+    let hash = scene.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    Some(hash)
+}
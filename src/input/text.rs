@@ -0,0 +1,121 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::ElementState,
+    window::Window,
+};
+
+use crate::{
+    ecs::{Events, Resources},
+    input::event::InputEvent,
+};
+
+/// Text typed by the user, respecting the keyboard layout and modifiers, including text committed
+/// through an input method editor.
+///
+/// Control characters such as backspace or enter are not included, and should be read as key
+/// presses instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextInput(pub String);
+
+/// A change in the state of the input method editor used to compose text, e.g. for CJK input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImeEvent {
+    /// The input method editor was enabled.
+    Enabled,
+    /// The text currently being composed changed.
+    Preedit {
+        /// The text being composed, which is empty when composition ends.
+        text: String,
+        /// The byte range of the cursor or selection within the text, if it should be shown.
+        cursor: Option<(usize, usize)>,
+    },
+    /// Composition finished, inserting the given text. It is also sent as a [`TextInput`].
+    Commit(String),
+    /// The input method editor was disabled.
+    Disabled,
+}
+
+/// Controls whether the input method editor is allowed and where its candidate box is shown,
+/// applied to the window at the end of every frame.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImeControl {
+    /// Whether the input method editor is allowed, i.e. a text field is focused.
+    enabled: bool,
+    /// The area of the text being edited in physical pixels, which the candidate box avoids.
+    cursor_area: Option<(Vec2, Vec2)>,
+
+    /// The state last applied to the window.
+    applied: Option<(bool, Option<(Vec2, Vec2)>)>,
+}
+
+impl ImeControl {
+    /// Allows or disallows the input method editor, e.g. when a text field gains or loses focus.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Returns if the input method editor is allowed.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Sets the area of the text being edited in physical pixels, relative to the top left corner
+    /// of the window, so that the candidate box is shown next to it.
+    pub fn set_cursor_area(&mut self, position: Vec2, size: Vec2) {
+        self.cursor_area = Some((position, size));
+    }
+
+    /// Returns the area of the text being edited in physical pixels, if set.
+    pub fn cursor_area(&self) -> Option<(Vec2, Vec2)> {
+        self.cursor_area
+    }
+
+    /// Applies any changes to the window.
+    pub(crate) fn apply(&mut self, window: &Window) {
+        let state = (self.enabled, self.cursor_area);
+
+        if self.applied == Some(state) {
+            return;
+        }
+
+        window.set_ime_allowed(self.enabled);
+
+        if let Some((position, size)) = self.cursor_area {
+            window.set_ime_cursor_area(
+                PhysicalPosition::new(position.x, position.y),
+                PhysicalSize::new(size.x, size.y),
+            );
+        }
+
+        self.applied = Some(state);
+    }
+}
+
+/// Sends the [`TextInput`] and [`ImeEvent`] events for an incoming [`InputEvent`].
+pub(crate) fn input_event(resources: &mut Resources, event: &InputEvent) {
+    match event {
+        InputEvent::Key {
+            state: ElementState::Pressed,
+            text: Some(text),
+            ..
+        } if !text.chars().all(char::is_control) => {
+            resources
+                .get_mut::<Events<TextInput>>()
+                .send(TextInput(text.clone()));
+        }
+
+        InputEvent::Ime(event) => {
+            if let ImeEvent::Commit(text) = event {
+                resources
+                    .get_mut::<Events<TextInput>>()
+                    .send(TextInput(text.clone()));
+            }
+
+            resources.get_mut::<Events<ImeEvent>>().send(event.clone());
+        }
+
+        _ => {}
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+
+use glam::Vec2;
+use winit::event::TouchPhase;
+
+/// A single finger currently touching the screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchPoint {
+    /// The identifier of the finger, unique while it touches the screen.
+    pub id: u64,
+    /// The position the finger first touched in physical pixels.
+    pub start_position: Vec2,
+    /// The current position of the finger in physical pixels.
+    pub position: Vec2,
+    /// The position of the finger at the start of this frame in physical pixels.
+    pub previous_position: Vec2,
+    /// The real time in seconds the finger first touched the screen.
+    pub start_time: f64,
+}
+
+/// A gesture recognized from one or more fingers touching the screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// A single finger briefly touched the screen without moving.
+    Tap {
+        /// The position of the tap in physical pixels.
+        position: Vec2,
+    },
+    /// A second tap closely following a previous one, sent after its [`Gesture::Tap`].
+    DoubleTap {
+        /// The position of the second tap in physical pixels.
+        position: Vec2,
+    },
+    /// A single finger touched the screen without moving for a while, sent once while it is held.
+    LongPress {
+        /// The position of the finger in physical pixels.
+        position: Vec2,
+    },
+    /// A single finger quickly moved across the screen and lifted off.
+    Swipe {
+        /// The position the swipe started at in physical pixels.
+        start: Vec2,
+        /// The position the swipe ended at in physical pixels.
+        end: Vec2,
+        /// The average velocity of the swipe in physical pixels per second.
+        velocity: Vec2,
+    },
+    /// Two fingers moved closer together or further apart this frame.
+    Pinch {
+        /// The point between both fingers in physical pixels.
+        center: Vec2,
+        /// The ratio between the distance of both fingers now and at the start of the frame.
+        scale: f32,
+    },
+    /// Two fingers rotated around each other this frame.
+    Rotate {
+        /// The point between both fingers in physical pixels.
+        center: Vec2,
+        /// The counter-clockwise angle both fingers rotated by in radians, as seen on screen.
+        angle: f32,
+    },
+}
+
+/// The thresholds used to recognize gestures, in seconds and physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureSettings {
+    /// The longest a finger may touch the screen to count as a tap.
+    pub tap_max_duration: f64,
+    /// The furthest a finger may move to count as a tap or long press.
+    pub tap_max_distance: f32,
+    /// The longest time between two taps to count as a double tap.
+    pub double_tap_interval: f64,
+    /// How long a finger must be held without moving to count as a long press.
+    pub long_press_duration: f64,
+    /// The shortest distance a finger must move to count as a swipe.
+    pub swipe_min_distance: f32,
+    /// The longest a finger may touch the screen to count as a swipe.
+    pub swipe_max_duration: f64,
+}
+
+/// Tracks every finger touching the screen and recognizes gestures from their movement.
+#[derive(Debug, Clone, Default)]
+pub struct TouchState {
+    /// All fingers currently touching the screen, keyed by their identifier.
+    touches: BTreeMap<u64, TouchPoint>,
+    /// The fingers which started touching the screen this frame.
+    started: Vec<TouchPoint>,
+    /// The fingers which lifted off or were cancelled this frame.
+    ended: Vec<TouchPoint>,
+
+    /// The gestures recognized this frame.
+    gestures: Vec<Gesture>,
+    /// The thresholds used to recognize gestures.
+    settings: GestureSettings,
+
+    /// The real time in seconds at the start of the current frame.
+    time: f64,
+    /// The time and position of the last tap, used to detect double taps.
+    last_tap: Option<(f64, Vec2)>,
+    /// Whether the current touch session involved more than one finger, suppressing single
+    /// finger gestures until all fingers lift off.
+    multi_touch: bool,
+    /// Whether a long press was already recognized for the current touch session.
+    long_pressed: bool,
+}
+
+impl TouchState {
+    /// Returns an iterator over all fingers currently touching the screen.
+    pub fn iter(&self) -> impl Iterator<Item = &TouchPoint> {
+        self.touches.values()
+    }
+
+    /// Returns the finger with the given identifier, if it is touching the screen.
+    pub fn get(&self, id: u64) -> Option<&TouchPoint> {
+        self.touches.get(&id)
+    }
+
+    /// Returns the number of fingers currently touching the screen.
+    pub fn count(&self) -> usize {
+        self.touches.len()
+    }
+
+    /// Returns the fingers which started touching the screen this frame.
+    pub fn started(&self) -> &[TouchPoint] {
+        &self.started
+    }
+
+    /// Returns the fingers which lifted off or were cancelled this frame.
+    pub fn ended(&self) -> &[TouchPoint] {
+        &self.ended
+    }
+
+    /// Returns the gestures recognized this frame.
+    pub fn gestures(&self) -> &[Gesture] {
+        &self.gestures
+    }
+
+    /// Returns the thresholds used to recognize gestures.
+    pub fn settings(&self) -> &GestureSettings {
+        &self.settings
+    }
+
+    /// Sets the thresholds used to recognize gestures.
+    pub fn set_settings(&mut self, settings: GestureSettings) {
+        self.settings = settings;
+    }
+
+    /// Handles an incoming touch event of the given finger at a position in physical pixels.
+    pub(crate) fn event(&mut self, id: u64, phase: TouchPhase, position: Vec2) {
+        match phase {
+            TouchPhase::Started => {
+                if self.touches.is_empty() {
+                    self.multi_touch = false;
+                    self.long_pressed = false;
+                }
+
+                let point = TouchPoint {
+                    id,
+                    start_position: position,
+                    position,
+                    previous_position: position,
+                    start_time: self.time,
+                };
+
+                self.touches.insert(id, point);
+                self.started.push(point);
+                self.multi_touch |= self.touches.len() > 1;
+            }
+            TouchPhase::Moved => {
+                if let Some(point) = self.touches.get_mut(&id) {
+                    point.position = position;
+                }
+            }
+            TouchPhase::Ended => {
+                if let Some(mut point) = self.touches.remove(&id) {
+                    point.position = position;
+                    self.ended.push(point);
+                    self.recognize_lift_off(&point);
+                }
+            }
+            TouchPhase::Cancelled => {
+                if let Some(point) = self.touches.remove(&id) {
+                    self.ended.push(point);
+                }
+            }
+        }
+    }
+
+    /// Recognizes the gestures completed by a single finger lifting off the screen.
+    fn recognize_lift_off(&mut self, point: &TouchPoint) {
+        if self.multi_touch || self.long_pressed {
+            return;
+        }
+
+        let duration = self.time - point.start_time;
+        let offset = point.position - point.start_position;
+        let settings = self.settings;
+
+        if duration <= settings.tap_max_duration && offset.length() <= settings.tap_max_distance {
+            self.gestures.push(Gesture::Tap {
+                position: point.position,
+            });
+
+            let double_tap = self.last_tap.is_some_and(|(time, position)| {
+                self.time - time <= settings.double_tap_interval
+                    && position.distance(point.position) <= settings.tap_max_distance
+            });
+
+            if double_tap {
+                self.gestures.push(Gesture::DoubleTap {
+                    position: point.position,
+                });
+                self.last_tap = None;
+            } else {
+                self.last_tap = Some((self.time, point.position));
+            }
+        } else if duration <= settings.swipe_max_duration
+            && offset.length() >= settings.swipe_min_distance
+        {
+            self.gestures.push(Gesture::Swipe {
+                start: point.start_position,
+                end: point.position,
+                velocity: offset / duration.max(f64::EPSILON) as f32,
+            });
+        }
+    }
+
+    /// Recognizes the continuous gestures made since the start of the frame, given the current
+    /// real time in seconds.
+    pub(crate) fn update(&mut self, time: f64) {
+        self.time = time;
+
+        let mut touches = self.touches.values();
+
+        match (touches.next(), touches.next()) {
+            (Some(point), None) if !self.multi_touch && !self.long_pressed => {
+                let held = time - point.start_time >= self.settings.long_press_duration;
+                let moved = point.position.distance(point.start_position);
+
+                if held && moved <= self.settings.tap_max_distance {
+                    self.gestures.push(Gesture::LongPress {
+                        position: point.position,
+                    });
+                    self.long_pressed = true;
+                }
+            }
+            (Some(a), Some(b)) => {
+                let previous = b.previous_position - a.previous_position;
+                let current = b.position - a.position;
+                let center = (a.position + b.position) * 0.5;
+
+                if previous.length() > 0.0 && current.length() > 0.0 {
+                    let scale = current.length() / previous.length();
+                    // screen space has y pointing down, so flip the angle to be counter-clockwise
+                    let angle = -previous.angle_to(current);
+
+                    if scale != 1.0 {
+                        self.gestures.push(Gesture::Pinch { center, scale });
+                    }
+
+                    if angle != 0.0 {
+                        self.gestures.push(Gesture::Rotate { center, angle });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Flushes all per-frame state to begin a new frame.
+    pub(crate) fn flush(&mut self) {
+        self.started.clear();
+        self.ended.clear();
+        self.gestures.clear();
+
+        for point in self.touches.values_mut() {
+            point.previous_position = point.position;
+        }
+    }
+}
+
+impl Default for GestureSettings {
+    fn default() -> Self {
+        Self {
+            tap_max_duration: 0.3,
+            tap_max_distance: 20.0,
+            double_tap_interval: 0.3,
+            long_press_duration: 0.5,
+            swipe_min_distance: 80.0,
+            swipe_max_duration: 0.5,
+        }
+    }
+}
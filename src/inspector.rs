@@ -0,0 +1,321 @@
+use std::any::TypeId;
+
+use egui::{
+    CollapsingHeader, Color32, DragValue, ScrollArea, Sense, Shape, Stroke, Ui, pos2, vec2,
+};
+use glam::Vec3;
+use hecs::Entity;
+use winit::{
+    event::{ElementState, KeyEvent, WindowEvent},
+    keyboard::{KeyCode, PhysicalKey},
+    window::Window,
+};
+
+use crate::{
+    diagnostics::FrameDiagnostics,
+    ecs::{Resources, World},
+    reflect::TypeRegistry,
+    renderer::{overlay::OverlayFrame, sprite::Material2D},
+    transform::Transform,
+};
+
+/// The settings of the entity inspector overlay, inserted by
+/// [`ApplicationBuilder::with_inspector`](crate::application::ApplicationBuilder::with_inspector).
+#[derive(Debug, Clone, Copy)]
+pub struct InspectorSettings {
+    /// The key which toggles the overlay.
+    pub toggle_key: KeyCode,
+}
+
+/// An overlay listing all entities in the world along with their reflected components.
+pub(crate) struct Inspector {
+    /// The key which toggles the overlay.
+    toggle_key: KeyCode,
+    /// Whether the overlay is currently shown.
+    open: bool,
+    /// The entity currently selected for inspection.
+    selected: Option<Entity>,
+
+    /// The egui context the overlay is laid out with.
+    context: egui::Context,
+    /// The winit integration translating window events for egui.
+    state: egui_winit::State,
+}
+
+impl Inspector {
+    /// Creates a new, closed [`Inspector`] for the given window.
+    pub fn new(window: &Window, settings: InspectorSettings) -> Self {
+        let context = egui::Context::default();
+        let state = egui_winit::State::new(
+            context.clone(),
+            egui::ViewportId::ROOT,
+            window,
+            Some(window.scale_factor() as f32),
+            window.theme(),
+            None,
+        );
+
+        Self {
+            toggle_key: settings.toggle_key,
+            open: false,
+            selected: None,
+            context,
+            state,
+        }
+    }
+
+    /// Handles an incoming [`WindowEvent`], returning if the overlay consumed it.
+    ///
+    /// Key and mouse button releases are never consumed, even when the overlay wants them, as the
+    /// game may have seen the matching press and would otherwise consider it held forever.
+    pub fn window_event(&mut self, window: &Window, event: &WindowEvent) -> bool {
+        if let WindowEvent::KeyboardInput {
+            event:
+                KeyEvent {
+                    physical_key: PhysicalKey::Code(code),
+                    state: ElementState::Pressed,
+                    repeat: false,
+                    ..
+                },
+            ..
+        } = event
+            && *code == self.toggle_key
+        {
+            self.open = !self.open;
+            return true;
+        }
+
+        let consumed = self.open && self.state.on_window_event(window, event).consumed;
+
+        let released = matches!(
+            event,
+            WindowEvent::KeyboardInput {
+                event: KeyEvent {
+                    state: ElementState::Released,
+                    ..
+                },
+                ..
+            } | WindowEvent::MouseInput {
+                state: ElementState::Released,
+                ..
+            }
+        );
+
+        consumed && !released
+    }
+
+    /// Lays out the overlay for this frame, returning the frame to draw if it is open.
+    pub fn run(
+        &mut self,
+        window: &Window,
+        world: &mut World,
+        resources: &Resources,
+    ) -> Option<OverlayFrame> {
+        let input = self.state.take_egui_input(window);
+
+        if !self.open {
+            return None;
+        }
+
+        let context = self.context.clone();
+        let output = context.run(input, |ctx| {
+            self.ui(ctx, world, resources.get::<TypeRegistry>());
+            frame_time_ui(ctx, resources.get::<FrameDiagnostics>());
+        });
+
+        self.state
+            .handle_platform_output(window, output.platform_output);
+
+        Some(OverlayFrame {
+            primitives: context.tessellate(output.shapes, output.pixels_per_point),
+            textures_delta: output.textures_delta,
+            pixels_per_point: output.pixels_per_point,
+        })
+    }
+
+    /// Lays out the inspector window.
+    fn ui(&mut self, ctx: &egui::Context, world: &mut World, registry: &TypeRegistry) {
+        egui::Window::new("Inspector")
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                self.entity_list(ui, world);
+                ui.separator();
+
+                let Some(entity) = self.selected.filter(|entity| world.contains(*entity)) else {
+                    ui.label("no entity selected");
+                    return;
+                };
+
+                Self::entity_components(ui, world, registry, entity);
+            });
+    }
+
+    /// Lays out the list of all entities, allowing one to be selected.
+    fn entity_list(&mut self, ui: &mut Ui, world: &World) {
+        let mut entities = world
+            .iter()
+            .map(|entity| entity.entity())
+            .collect::<Vec<_>>();
+        entities.sort_by_key(|entity| entity.id());
+
+        ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+            for entity in entities {
+                let selected = self.selected == Some(entity);
+
+                if ui
+                    .selectable_label(selected, format!("{entity:?}"))
+                    .clicked()
+                {
+                    self.selected = Some(entity);
+                }
+            }
+        });
+    }
+
+    /// Lays out all reflected components of an entity, with editors for built-in types.
+    fn entity_components(ui: &mut Ui, world: &mut World, registry: &TypeRegistry, entity: Entity) {
+        if let Ok(mut transform) = world.get::<&mut Transform>(entity) {
+            CollapsingHeader::new("Transform")
+                .default_open(true)
+                .show(ui, |ui| transform_editor(ui, &mut transform));
+        }
+
+        if let Ok(mut material) = world.get::<&mut Material2D>(entity) {
+            CollapsingHeader::new("Material2D")
+                .default_open(true)
+                .show(ui, |ui| material_editor(ui, &mut material));
+        }
+
+        let edited = [TypeId::of::<Transform>(), TypeId::of::<Material2D>()];
+
+        for registration in registry.components_of(world, entity) {
+            if edited.contains(&registration.type_id()) {
+                continue;
+            }
+
+            CollapsingHeader::new(registration.name())
+                .default_open(true)
+                .show(ui, |ui| {
+                    for field in registration.fields() {
+                        match registration.get(world, entity, field) {
+                            Some(value) => ui.label(format!("{field}: {value}")),
+                            None => ui.weak(format!("{field}: -")),
+                        };
+                    }
+                });
+        }
+    }
+}
+
+/// Lays out a window graphing the recent frame times along with their statistics.
+fn frame_time_ui(ctx: &egui::Context, diagnostics: &FrameDiagnostics) {
+    egui::Window::new("Frame times")
+        .default_width(320.0)
+        .show(ctx, |ui| {
+            let stats = [
+                ("min", diagnostics.min()),
+                ("avg", diagnostics.avg()),
+                ("max", diagnostics.max()),
+                ("1% low", diagnostics.one_percent_low()),
+                ("0.1% low", diagnostics.point_one_percent_low()),
+            ];
+
+            ui.horizontal_wrapped(|ui| {
+                for (label, frame_time) in stats {
+                    let frame_time = frame_time.unwrap_or_default() * 1000.0;
+                    ui.label(format!("{label}: {frame_time:.2} ms"));
+                }
+            });
+
+            frame_time_graph(ui, diagnostics);
+        });
+}
+
+/// Lays out a line graph of the recent frame times, scaled to the slowest frame.
+fn frame_time_graph(ui: &mut Ui, diagnostics: &FrameDiagnostics) {
+    let (response, painter) =
+        ui.allocate_painter(vec2(ui.available_width(), 100.0), Sense::hover());
+    let rect = response.rect;
+
+    painter.rect_filled(rect, 0.0, Color32::from_black_alpha(96));
+
+    let peak = diagnostics.max().unwrap_or_default().max(1.0 / 60.0);
+    let step = rect.width() / diagnostics.capacity().saturating_sub(1).max(1) as f32;
+
+    let points = diagnostics
+        .history()
+        .enumerate()
+        .map(|(i, frame_time)| {
+            pos2(
+                rect.left() + i as f32 * step,
+                rect.bottom() - frame_time / peak * rect.height(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // reference line at 60 fps, so hitches stand out against the target
+    let target = rect.bottom() - (1.0 / 60.0) / peak * rect.height();
+    painter.hline(
+        rect.x_range(),
+        target,
+        Stroke::new(1.0, Color32::DARK_GREEN),
+    );
+    painter.add(Shape::line(points, Stroke::new(1.0, Color32::LIGHT_GRAY)));
+}
+
+/// Lays out an editor for a [`Transform`].
+fn transform_editor(ui: &mut Ui, transform: &mut Transform) {
+    vec3_editor(ui, "translation", &mut transform.translation);
+    vec3_editor(ui, "scale", &mut transform.scale);
+
+    let mut angle = transform.angle_2d().to_degrees();
+
+    ui.horizontal(|ui| {
+        ui.label("rotation");
+
+        if ui
+            .add(DragValue::new(&mut angle).speed(1.0).suffix("°"))
+            .changed()
+        {
+            transform.set_angle_2d(angle.to_radians());
+        }
+    });
+}
+
+/// Lays out an editor for a [`Material2D`].
+fn material_editor(ui: &mut Ui, material: &mut Material2D) {
+    match material {
+        Material2D::FlatColor { r, g, b } => {
+            let mut color = [*r, *g, *b];
+
+            ui.horizontal(|ui| {
+                ui.label("color");
+
+                if ui.color_edit_button_rgb(&mut color).changed() {
+                    [*r, *g, *b] = color;
+                }
+            });
+        }
+        Material2D::Textured { texture } => {
+            let mut path = texture.to_string();
+
+            ui.horizontal(|ui| {
+                ui.label("texture");
+
+                if ui.text_edit_singleline(&mut path).changed() {
+                    *texture = path.into();
+                }
+            });
+        }
+    }
+}
+
+/// Lays out a labelled editor for each component of a [`Vec3`].
+fn vec3_editor(ui: &mut Ui, label: &str, value: &mut Vec3) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(DragValue::new(&mut value.x).speed(0.05));
+        ui.add(DragValue::new(&mut value.y).speed(0.05));
+        ui.add(DragValue::new(&mut value.z).speed(0.05));
+    });
+}
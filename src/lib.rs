@@ -1,8 +1,19 @@
 pub mod application;
+mod clock;
+mod diagnostics;
 mod ecs;
+#[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+mod hot_reload;
 mod input;
+#[cfg(feature = "inspector")]
+mod inspector;
+mod name;
 pub mod prelude;
+mod profiler;
+pub mod reflect;
 mod renderer;
+mod scene;
+mod spatial;
 mod timer;
 mod transform;
 
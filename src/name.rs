@@ -0,0 +1,210 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap, HashSet},
+};
+
+use hecs::Entity;
+use serde::{Deserialize, Serialize};
+
+use crate::ecs::{Lifecycle, Resources, Schedule, World};
+
+/// A human readable name of an entity, which can be looked up through the [`NameIndex`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Name(pub Cow<'static, str>);
+
+/// A set of human readable tags attached to an entity, which can be queried through the
+/// [`NameIndex`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tags(pub BTreeSet<Cow<'static, str>>);
+
+/// An index of all named and tagged entities, updated whenever a [`Name`] or [`Tags`] component
+/// is added or removed.
+///
+/// The index is updated by observers, so it reflects changes made by a system once that system
+/// has finished. Changing a component in place isn't observed, so entities have to be renamed or
+/// retagged by removing their component and inserting the changed one.
+#[derive(Debug, Default)]
+pub struct NameIndex {
+    /// All named entities, keyed by their name.
+    names: HashMap<Cow<'static, str>, HashSet<Entity>>,
+    /// The name every named entity is indexed under.
+    entity_names: HashMap<Entity, Cow<'static, str>>,
+    /// All tagged entities, keyed by each of their tags.
+    tags: HashMap<Cow<'static, str>, HashSet<Entity>>,
+    /// The tags every tagged entity is indexed under.
+    entity_tags: HashMap<Entity, BTreeSet<Cow<'static, str>>>,
+}
+
+crate::reflect!(Name { 0 });
+crate::reflect!(Tags { 0 });
+
+impl Name {
+    /// Creates a new [`Name`].
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self(name.into())
+    }
+
+    /// Returns the name as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Tags {
+    /// Creates a new set of [`Tags`] from the given tags.
+    pub fn new<T: Into<Cow<'static, str>>>(tags: impl IntoIterator<Item = T>) -> Self {
+        Self(tags.into_iter().map(Into::into).collect())
+    }
+
+    /// Adds a tag to the set.
+    pub fn insert(&mut self, tag: impl Into<Cow<'static, str>>) {
+        self.0.insert(tag.into());
+    }
+
+    /// Removes a tag from the set, returning if it was present.
+    pub fn remove(&mut self, tag: &str) -> bool {
+        self.0.remove(tag)
+    }
+
+    /// Returns if the set contains the given tag.
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.contains(tag)
+    }
+
+    /// Returns an iterator over all tags in the set.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|tag| tag.as_ref())
+    }
+}
+
+impl NameIndex {
+    /// Returns the entity with the given name.
+    ///
+    /// If multiple entities share a name, any one of them may be returned.
+    pub fn get(&self, name: &str) -> Option<Entity> {
+        self.names.get(name)?.iter().next().copied()
+    }
+
+    /// Returns an iterator over all entities with the given tag.
+    pub fn tagged(&self, tag: &str) -> impl Iterator<Item = Entity> + '_ {
+        self.tags.get(tag).into_iter().flatten().copied()
+    }
+
+    /// Returns an iterator over all entities with every one of the given tags.
+    pub fn tagged_all<'a>(&'a self, tags: &'a [&str]) -> impl Iterator<Item = Entity> + 'a {
+        let sets = tags
+            .iter()
+            .map(|tag| self.tags.get(*tag))
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default();
+
+        // only the smallest set has to be walked, as every result has to be part of it
+        let smallest = sets.iter().copied().min_by_key(|set| set.len());
+
+        smallest
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(move |entity| sets.iter().all(|set| set.contains(entity)))
+    }
+
+    /// Returns an iterator over all entities with at least one of the given tags.
+    pub fn tagged_any<'a>(&'a self, tags: &'a [&str]) -> impl Iterator<Item = Entity> + 'a {
+        let mut seen = HashSet::new();
+
+        tags.iter()
+            .flat_map(|tag| self.tagged(tag))
+            .filter(move |entity| seen.insert(*entity))
+    }
+
+    /// Registers the observers keeping the index up to date.
+    pub(crate) fn observe(schedule: &mut Schedule) {
+        schedule.add_observer::<Name, _>(Lifecycle::Add, Self::name_added);
+        schedule.add_observer::<Name, _>(Lifecycle::Remove, Self::name_removed);
+        schedule.add_observer::<Tags, _>(Lifecycle::Add, Self::tags_added);
+        schedule.add_observer::<Tags, _>(Lifecycle::Remove, Self::tags_removed);
+    }
+
+    /// Indexes the name of an entity which just received a [`Name`].
+    fn name_added(world: &mut World, resources: &mut Resources, entity: Entity) {
+        // the entity may already have been despawned again, which its remove observer handles
+        let Ok(name) = world.get::<&Name>(entity) else {
+            return;
+        };
+
+        let index = resources.get_mut::<NameIndex>();
+        index.unindex_name(entity);
+        index
+            .names
+            .entry(name.0.clone())
+            .or_default()
+            .insert(entity);
+        index.entity_names.insert(entity, name.0.clone());
+    }
+
+    /// Removes the name of an entity which just lost its [`Name`] from the index.
+    fn name_removed(_: &mut World, resources: &mut Resources, entity: Entity) {
+        resources.get_mut::<NameIndex>().unindex_name(entity);
+    }
+
+    /// Indexes the tags of an entity which just received [`Tags`].
+    fn tags_added(world: &mut World, resources: &mut Resources, entity: Entity) {
+        let Ok(tags) = world.get::<&Tags>(entity) else {
+            return;
+        };
+
+        let index = resources.get_mut::<NameIndex>();
+        index.unindex_tags(entity);
+
+        for tag in &tags.0 {
+            index.tags.entry(tag.clone()).or_default().insert(entity);
+        }
+
+        index.entity_tags.insert(entity, tags.0.clone());
+    }
+
+    /// Removes the tags of an entity which just lost its [`Tags`] from the index.
+    fn tags_removed(_: &mut World, resources: &mut Resources, entity: Entity) {
+        resources.get_mut::<NameIndex>().unindex_tags(entity);
+    }
+
+    /// Removes an entity from the name index.
+    fn unindex_name(&mut self, entity: Entity) {
+        let Some(name) = self.entity_names.remove(&entity) else {
+            return;
+        };
+
+        if let Some(entities) = self.names.get_mut(&name) {
+            entities.remove(&entity);
+
+            if entities.is_empty() {
+                self.names.remove(&name);
+            }
+        }
+    }
+
+    /// Removes an entity from the tag index.
+    fn unindex_tags(&mut self, entity: Entity) {
+        for tag in self.entity_tags.remove(&entity).into_iter().flatten() {
+            if let Some(entities) = self.tags.get_mut(&tag) {
+                entities.remove(&entity);
+
+                if entities.is_empty() {
+                    self.tags.remove(&tag);
+                }
+            }
+        }
+    }
+}
+
+impl From<&'static str> for Name {
+    fn from(name: &'static str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for Name {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
@@ -1,7 +1,34 @@
-pub use crate::application::Application;
-pub use crate::ecs::{Resources, System, World};
-pub use crate::input::InputState;
-pub use crate::renderer::camera::Camera2D;
+pub use crate::application::{Application, Headless};
+pub use crate::clock::{Clock, FixedClock, ManualClock, SystemClock};
+pub use crate::diagnostics::{FrameDiagnostics, HistogramBucket};
+pub use crate::ecs::{
+    Events, Lifecycle, Observer, Resources, Schedule, System, SystemControl, Trigger, World,
+};
+#[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+pub use crate::hot_reload::{HotReloadSettings, REGISTER_SYMBOL, RegisterFn};
+#[cfg(feature = "gilrs")]
+pub use crate::input::gamepad::GilrsBackend;
+pub use crate::input::{
+    Chord, InputState,
+    actions::{Axis2D, Binding, InputMap},
+    buffer::{BufferWindow, BufferedInput, BufferedPress, Direction, InputBuffer, Sequence},
+    event::InputEvent,
+    gamepad::{
+        Gamepad, GamepadAxis, GamepadBackend, GamepadButton, GamepadEvent, GamepadId, GamepadStick,
+        VirtualGamepad,
+    },
+    replay::{Replay, ReplayFrame, ReplaySettings, ReplayStatus},
+    text::{ImeControl, ImeEvent, TextInput},
+    touch::{Gesture, GestureSettings, TouchPoint, TouchState},
+};
+#[cfg(feature = "inspector")]
+pub use crate::inspector::InspectorSettings;
+pub use crate::name::{Name, NameIndex, Tags};
+pub use crate::profiler::{Profiler, Span};
+pub use crate::reflect::{Field, Reflect, TypeRegistration, TypeRegistry};
+pub use crate::renderer::camera::{Camera2D, Viewport};
 pub use crate::renderer::sprite::{Material2D, Mesh2D, Shape2D};
-pub use crate::timer::FrameTimer;
+pub use crate::scene::{EntityMap, MapEntities, Scene, SceneEntity, entity_serde};
+pub use crate::spatial::{Aabb, RayHit, SpatialIndex};
+pub use crate::timer::{DelayedCommands, FrameTimer, Lifetime, Stopwatch, Timer, TimerMode};
 pub use crate::transform::Transform;
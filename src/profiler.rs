@@ -0,0 +1,169 @@
+use std::{borrow::Cow, path::PathBuf};
+
+use serde_json::json;
+use web_time::{Duration, Instant};
+
+/// Records CPU timings of systems and rendering stages, optionally exporting them as a trace.
+pub struct Profiler {
+    /// The instant all span start times are measured relative to.
+    epoch: Instant,
+    /// The start of the frame currently being recorded.
+    frame_start: Instant,
+
+    /// The spans recorded during the frame currently being recorded.
+    current_frame: Vec<Span>,
+    /// The spans recorded during the last completed frame.
+    last_frame: Vec<Span>,
+
+    /// The trace currently being recorded, if any.
+    trace: Option<TraceRecording>,
+}
+
+/// A single timed span of work.
+#[derive(Debug, Clone)]
+pub struct Span {
+    /// The name of the work that was timed, i.e. the system's label or type name.
+    pub name: Cow<'static, str>,
+    /// The start of the span, relative to when the profiler was created.
+    pub start: Duration,
+    /// How long the span took.
+    pub duration: Duration,
+}
+
+/// A Chrome/Perfetto trace being recorded over a number of frames.
+struct TraceRecording {
+    /// The path the trace is written to once complete.
+    path: PathBuf,
+    /// The number of frames left to record.
+    frames_left: u32,
+    /// All spans recorded so far.
+    spans: Vec<Span>,
+}
+
+impl Profiler {
+    /// Creates a new [`Profiler`].
+    pub fn new() -> Self {
+        Self {
+            epoch: Instant::now(),
+            frame_start: Instant::now(),
+            current_frame: Vec::new(),
+            last_frame: Vec::new(),
+            trace: None,
+        }
+    }
+
+    /// Records a span of work between two instants.
+    pub fn record(&mut self, name: impl Into<Cow<'static, str>>, start: Instant, end: Instant) {
+        self.current_frame.push(Span {
+            name: name.into(),
+            start: start.duration_since(self.epoch),
+            duration: end.duration_since(start),
+        });
+    }
+
+    /// Returns all spans recorded during the last completed frame, in the order they finished.
+    pub fn last_frame(&self) -> &[Span] {
+        &self.last_frame
+    }
+
+    /// Returns the total time spent in all spans with the given name during the last frame.
+    pub fn timing(&self, name: &str) -> Option<Duration> {
+        self.last_frame
+            .iter()
+            .filter(|span| span.name == name)
+            .map(|span| span.duration)
+            .reduce(|total, duration| total + duration)
+    }
+
+    /// Starts recording a Chrome/Perfetto trace of the next `frames` frames, to be written as JSON
+    /// to the given path.
+    pub fn record_trace(&mut self, path: impl Into<PathBuf>, frames: u32) {
+        self.trace = Some(TraceRecording {
+            path: path.into(),
+            frames_left: frames,
+            spans: Vec::new(),
+        });
+    }
+
+    /// Returns if a trace is currently being recorded.
+    pub fn is_recording_trace(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    /// Discards all recorded spans along with any trace being recorded.
+    ///
+    /// Used before unloading a hot reloadable library, as span names may point into it.
+    #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+    pub(crate) fn clear(&mut self) {
+        self.current_frame.clear();
+        self.last_frame.clear();
+
+        if let Some(trace) = self.trace.take() {
+            log::warn!(
+                "discarded trace {} as the gameplay library was reloaded",
+                trace.path.display()
+            );
+        }
+    }
+
+    /// Marks the start of a new frame.
+    pub(crate) fn begin_frame(&mut self) {
+        self.frame_start = Instant::now();
+    }
+
+    /// Marks the end of the current frame, writing out the trace if it is complete.
+    pub(crate) fn end_frame(&mut self) {
+        self.record("frame", self.frame_start, Instant::now());
+        self.last_frame = std::mem::take(&mut self.current_frame);
+
+        let Some(trace) = &mut self.trace else {
+            return;
+        };
+
+        trace.spans.extend(self.last_frame.iter().cloned());
+        trace.frames_left = trace.frames_left.saturating_sub(1);
+
+        if trace.frames_left > 0 {
+            return;
+        }
+
+        let Some(trace) = self.trace.take() else {
+            return;
+        };
+
+        match trace.write() {
+            Ok(()) => log::info!("wrote trace to {}", trace.path.display()),
+            Err(e) => log::error!("failed to write trace to {}: {e}", trace.path.display()),
+        }
+    }
+}
+
+impl TraceRecording {
+    /// Writes the recorded spans to the trace's path in the Chrome trace event format.
+    fn write(&self) -> std::io::Result<()> {
+        let events = self
+            .spans
+            .iter()
+            .map(|span| {
+                json!({
+                    "name": span.name,
+                    "cat": "ferret",
+                    "ph": "X",
+                    "ts": span.start.as_secs_f64() * 1_000_000.0,
+                    "dur": span.duration.as_secs_f64() * 1_000_000.0,
+                    "pid": 0,
+                    "tid": 0,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let trace = json!({ "traceEvents": events, "displayTimeUnit": "ms" });
+        std::fs::write(&self.path, trace.to_string())
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,360 @@
+use std::{any::TypeId, collections::HashMap};
+
+use anyhow::{Context, anyhow};
+use hecs::{Component, Entity, EntityBuilder};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::{
+    ecs::World,
+    name::{Name, Tags},
+    renderer::{
+        camera::Camera2D,
+        sprite::{Material2D, Mesh2D},
+    },
+    scene::{EntityMap, MapEntities},
+    timer::{Lifetime, Stopwatch, Timer},
+    transform::Transform,
+};
+
+/// A component type whose fields can be read and written by name at runtime, and which can be
+/// saved to and loaded from a [`Scene`](crate::scene::Scene) as a whole.
+///
+/// Usually implemented through the [`reflect!`](crate::reflect!) macro.
+pub trait Reflect: Component + Serialize + DeserializeOwned + Sized {
+    /// The name of the type.
+    const TYPE_NAME: &'static str;
+    /// All fields of the type, in declaration order.
+    const FIELDS: &'static [Field<Self>];
+}
+
+/// A single reflected field of a type, along with its accessors.
+pub struct Field<T> {
+    /// The name of the field; tuple fields are named by their index.
+    pub name: &'static str,
+    /// Reads the field's value, or returns `None` if it belongs to an inactive enum variant.
+    pub get: fn(&T) -> Option<Value>,
+    /// Writes a new value to the field.
+    pub set: fn(&mut T, Value) -> anyhow::Result<()>,
+}
+
+/// The registry of all reflected component types, keyed by their name, which is also the name
+/// they are stored under in scene files.
+pub struct TypeRegistry {
+    /// The registered types, keyed by their name.
+    types: HashMap<&'static str, TypeRegistration>,
+}
+
+/// The type erased accessors of a single reflected component type.
+pub struct TypeRegistration {
+    /// The name of the type.
+    name: &'static str,
+    /// The [`TypeId`] of the type.
+    type_id: TypeId,
+    /// The names of all fields of the type.
+    fields: Vec<&'static str>,
+
+    /// Returns if an entity has this component.
+    has: fn(&World, Entity) -> bool,
+    /// Reads a field of this component from an entity.
+    get: fn(&World, Entity, &str) -> Option<Value>,
+    /// Writes a field of this component on an entity.
+    set: fn(&mut World, Entity, &str, Value) -> anyhow::Result<()>,
+
+    /// Serializes the whole component from an entity, if it has one.
+    serialize: fn(&World, Entity) -> Option<anyhow::Result<Value>>,
+    /// Deserializes the whole component and adds it to an entity that's about to be spawned.
+    insert: fn(&mut EntityBuilder, Value) -> anyhow::Result<()>,
+    /// Remaps the entity references held by the component, if it holds any.
+    map_entities: Option<fn(&mut World, Entity, &EntityMap)>,
+}
+
+impl TypeRegistry {
+    /// Creates a new [`TypeRegistry`] with all built-in components registered.
+    pub fn new() -> Self {
+        let mut registry = Self {
+            types: HashMap::new(),
+        };
+
+        registry
+            .register::<Transform>()
+            .register::<Mesh2D>()
+            .register::<Material2D>()
+            .register::<Camera2D>()
+            .register::<Name>()
+            .register::<Tags>()
+            .register::<Timer>()
+            .register::<Stopwatch>()
+            .register::<Lifetime>();
+
+        registry
+    }
+
+    /// Registers a reflected component type.
+    pub fn register<T: Reflect>(&mut self) -> &mut Self {
+        self.types
+            .insert(T::TYPE_NAME, TypeRegistration::new::<T>(None));
+        self
+    }
+
+    /// Registers a reflected component type holding entity references, which are remapped when
+    /// spawning a [`Scene`](crate::scene::Scene).
+    pub fn register_mapped<T: Reflect + MapEntities>(&mut self) -> &mut Self {
+        self.types.insert(
+            T::TYPE_NAME,
+            TypeRegistration::new::<T>(Some(map_component_entities::<T>)),
+        );
+        self
+    }
+
+    /// Returns the registration of a type by its name.
+    pub fn get(&self, name: &str) -> Option<&TypeRegistration> {
+        self.types.get(name)
+    }
+
+    /// Returns an iterator over all registered types.
+    pub fn iter(&self) -> impl Iterator<Item = &TypeRegistration> {
+        self.types.values()
+    }
+
+    /// Returns an iterator over the registered types of all components an entity has.
+    pub fn components_of<'a>(
+        &'a self,
+        world: &'a World,
+        entity: Entity,
+    ) -> impl Iterator<Item = &'a TypeRegistration> {
+        self.iter()
+            .filter(move |registration| registration.has(world, entity))
+    }
+
+    /// Reads a field of a component on an entity, with both referred to by name.
+    pub fn get_field(
+        &self,
+        world: &World,
+        entity: Entity,
+        type_name: &str,
+        field: &str,
+    ) -> Option<Value> {
+        self.get(type_name)?.get(world, entity, field)
+    }
+
+    /// Writes a field of a component on an entity, with both referred to by name.
+    pub fn set_field(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        type_name: &str,
+        field: &str,
+        value: Value,
+    ) -> anyhow::Result<()> {
+        self.get(type_name)
+            .ok_or_else(|| anyhow!("unregistered type `{type_name}`"))?
+            .set(world, entity, field, value)
+    }
+}
+
+impl TypeRegistration {
+    /// Creates the registration of a reflected component type.
+    fn new<T: Reflect>(map_entities: Option<fn(&mut World, Entity, &EntityMap)>) -> Self {
+        Self {
+            name: T::TYPE_NAME,
+            type_id: TypeId::of::<T>(),
+            fields: T::FIELDS.iter().map(|field| field.name).collect(),
+            has: has_component::<T>,
+            get: get_field::<T>,
+            set: set_field::<T>,
+            serialize: serialize_component::<T>,
+            insert: insert_component::<T>,
+            map_entities,
+        }
+    }
+
+    /// Returns the name of the type.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the [`TypeId`] of the type.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Returns the names of all fields of the type.
+    pub fn fields(&self) -> &[&'static str] {
+        &self.fields
+    }
+
+    /// Returns if the entity has this component.
+    pub fn has(&self, world: &World, entity: Entity) -> bool {
+        (self.has)(world, entity)
+    }
+
+    /// Reads a field of this component from an entity.
+    pub fn get(&self, world: &World, entity: Entity, field: &str) -> Option<Value> {
+        (self.get)(world, entity, field)
+    }
+
+    /// Writes a field of this component on an entity.
+    pub fn set(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        field: &str,
+        value: Value,
+    ) -> anyhow::Result<()> {
+        (self.set)(world, entity, field, value)
+    }
+
+    /// Serializes the whole component from an entity, or returns `None` if it has none.
+    pub fn serialize(&self, world: &World, entity: Entity) -> Option<anyhow::Result<Value>> {
+        (self.serialize)(world, entity)
+    }
+
+    /// Deserializes the whole component and adds it to an entity that's about to be spawned.
+    pub(crate) fn insert(&self, builder: &mut EntityBuilder, value: Value) -> anyhow::Result<()> {
+        (self.insert)(builder, value)
+    }
+
+    /// Remaps the entity references held by the component on an entity, if it holds any.
+    pub(crate) fn map_entities(&self, world: &mut World, entity: Entity, map: &EntityMap) {
+        if let Some(map_entities) = self.map_entities {
+            map_entities(world, entity, map);
+        }
+    }
+}
+
+impl Default for TypeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Serializes a field's value, for use by [`reflect!`](crate::reflect!).
+pub fn read_field<F: Serialize>(field: &F) -> Option<Value> {
+    serde_json::to_value(field).ok()
+}
+
+/// Deserializes a new value into a field, for use by [`reflect!`](crate::reflect!).
+pub fn write_field<F: DeserializeOwned>(field: &mut F, value: Value) -> anyhow::Result<()> {
+    *field = serde_json::from_value(value)?;
+    Ok(())
+}
+
+/// Returns the error for writing a field of an inactive enum variant, for use by
+/// [`reflect!`](crate::reflect!).
+pub fn inactive_variant(type_name: &str, variant: &str) -> anyhow::Result<()> {
+    Err(anyhow!("`{type_name}` is not the `{variant}` variant"))
+}
+
+/// Returns if an entity has a component of type `T`.
+fn has_component<T: Reflect>(world: &World, entity: Entity) -> bool {
+    world.entity(entity).is_ok_and(|entity| entity.has::<T>())
+}
+
+/// Reads a field of a component of type `T` from an entity.
+fn get_field<T: Reflect>(world: &World, entity: Entity, name: &str) -> Option<Value> {
+    let component = world.get::<&T>(entity).ok()?;
+    let field = T::FIELDS.iter().find(|field| field.name == name)?;
+
+    (field.get)(&*component)
+}
+
+/// Writes a field of a component of type `T` on an entity.
+fn set_field<T: Reflect>(
+    world: &mut World,
+    entity: Entity,
+    name: &str,
+    value: Value,
+) -> anyhow::Result<()> {
+    let field = T::FIELDS
+        .iter()
+        .find(|field| field.name == name)
+        .ok_or_else(|| anyhow!("`{}` has no field `{name}`", T::TYPE_NAME))?;
+
+    let mut component = world
+        .get::<&mut T>(entity)
+        .with_context(|| format!("while accessing `{}`", T::TYPE_NAME))?;
+
+    (field.set)(&mut *component, value)
+        .with_context(|| format!("while writing `{}::{name}`", T::TYPE_NAME))
+}
+
+/// Serializes a component of type `T` from an entity, if it has one.
+fn serialize_component<T: Reflect>(world: &World, entity: Entity) -> Option<anyhow::Result<Value>> {
+    let component = world.get::<&T>(entity).ok()?;
+    Some(serde_json::to_value(&*component).map_err(Into::into))
+}
+
+/// Deserializes a component of type `T` and adds it to an entity that's about to be spawned.
+fn insert_component<T: Reflect>(builder: &mut EntityBuilder, value: Value) -> anyhow::Result<()> {
+    builder.add(serde_json::from_value::<T>(value)?);
+    Ok(())
+}
+
+/// Remaps the entity references held by a component of type `T` on an entity.
+fn map_component_entities<T: Reflect + MapEntities>(
+    world: &mut World,
+    entity: Entity,
+    map: &EntityMap,
+) {
+    if let Ok(mut component) = world.get::<&mut T>(entity) {
+        component.map_entities(map);
+    }
+}
+
+/// Implements [`Reflect`] for a struct or enum, listing the fields to expose.
+///
+/// Every field of the type, and every variant of an enum, must be listed; forgetting one fails to
+/// compile, so the list can't silently go stale.
+///
+/// ```ignore
+/// reflect!(Transform { translation, scale, rotation });
+/// reflect!(Mesh2D { 0 });
+/// reflect!(enum Material2D { FlatColor { r, g, b }, Textured { texture } });
+/// ```
+#[macro_export]
+macro_rules! reflect {
+    (enum $ty:ident { $($variant:ident { $($field:ident),* $(,)? }),* $(,)? }) => {
+        const _: fn(&$ty) = |value| match value {
+            $($ty::$variant { $($field: _),* } => {})*
+        };
+
+        #[allow(unreachable_patterns)]
+        impl $crate::reflect::Reflect for $ty {
+            const TYPE_NAME: &'static str = stringify!($ty);
+            const FIELDS: &'static [$crate::reflect::Field<Self>] = &[$($(
+                $crate::reflect::Field {
+                    name: stringify!($field),
+                    get: |value| match value {
+                        $ty::$variant { $field, .. } => $crate::reflect::read_field($field),
+                        _ => None,
+                    },
+                    set: |value, new| match value {
+                        $ty::$variant { $field, .. } => $crate::reflect::write_field($field, new),
+                        _ => $crate::reflect::inactive_variant(
+                            stringify!($ty),
+                            stringify!($variant),
+                        ),
+                    },
+                },
+            )*)*];
+        }
+    };
+
+    ($ty:ident { $($field:tt),* $(,)? }) => {
+        const _: fn(&$ty) = |value| {
+            let $ty { $($field: _),* } = value;
+        };
+
+        impl $crate::reflect::Reflect for $ty {
+            const TYPE_NAME: &'static str = stringify!($ty);
+            const FIELDS: &'static [$crate::reflect::Field<Self>] = &[$(
+                $crate::reflect::Field {
+                    name: stringify!($field),
+                    get: |value| $crate::reflect::read_field(&value.$field),
+                    set: |value, new| $crate::reflect::write_field(&mut value.$field, new),
+                },
+            )*];
+        }
+    };
+}
@@ -1,17 +1,31 @@
-use glam::Mat4;
+use glam::{Mat4, Vec2, Vec3};
+use hecs::Entity;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    ecs::World,
+    ecs::{Resources, World},
+    input::InputState,
     prelude::{Material2D, Mesh2D, Transform},
 };
 
 /// Represents a orthographic camera in 2D from which all 2D sprites will be rendered.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Camera2D {
     /// The size of the camera's view width in meters from the center of the screen.
     pub half_width: f32,
 }
 
+/// The size of the surface being rendered to in physical pixels, kept in sync with the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    /// The width of the surface in physical pixels.
+    pub width: u32,
+    /// The height of the surface in physical pixels.
+    pub height: u32,
+}
+
+crate::reflect!(Camera2D { half_width });
+
 impl Camera2D {
     /// Calculates the orthographic projection matrix based on the current camera and window state.
     pub fn projection_matrix(&self, aspect_ratio: f32) -> Mat4 {
@@ -31,12 +45,85 @@ impl Camera2D {
         transform.as_model_matrix().inverse()
     }
 
+    /// Calculates the combined matrix transforming world space into clip space.
+    ///
+    /// This is `projection * view`, so points are moved into view space before being projected.
+    /// The renderer uploads this same matrix to the sprite shader, which keeps rendering and the
+    /// screen to world conversions consistent.
+    pub fn view_projection_matrix(&self, transform: &Transform, aspect_ratio: f32) -> Mat4 {
+        self.projection_matrix(aspect_ratio) * Self::view_matrix(transform)
+    }
+
+    /// Converts a position in world space into physical pixels relative to the top left corner of
+    /// the window, as seen by this camera.
+    pub fn world_to_screen(&self, transform: &Transform, viewport: &Viewport, world: Vec3) -> Vec2 {
+        let ndc = self
+            .view_projection_matrix(transform, viewport.aspect_ratio())
+            .project_point3(world);
+
+        Vec2::new(ndc.x + 1.0, 1.0 - ndc.y) * 0.5 * viewport.size()
+    }
+
+    /// Converts a position in physical pixels relative to the top left corner of the window into
+    /// world space, as seen by this camera.
+    pub fn screen_to_world(
+        &self,
+        transform: &Transform,
+        viewport: &Viewport,
+        screen: Vec2,
+    ) -> Vec3 {
+        let uv = screen / viewport.size();
+        let ndc = Vec3::new(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0);
+
+        self.view_projection_matrix(transform, viewport.aspect_ratio())
+            .inverse()
+            .project_point3(ndc)
+    }
+
+    /// Returns the position of the cursor in world space as seen by the given camera entity, if
+    /// the cursor is inside the window.
+    pub fn cursor_world_position(
+        world: &World,
+        resources: &Resources,
+        camera: Entity,
+    ) -> Option<Vec2> {
+        let cursor = resources.get::<InputState>().cursor_position()?;
+        let viewport = resources.get::<Viewport>();
+
+        let mut query = world.query_one::<(&Camera2D, &Transform)>(camera).ok()?;
+        let (camera, transform) = query.get()?;
+
+        Some(
+            camera
+                .screen_to_world(transform, viewport, cursor)
+                .truncate(),
+        )
+    }
+
     /// Extracts the entities to be rendered from this camera.
     pub fn extract_entities(&self, world: &World) -> Vec<(Mesh2D, Material2D, Transform)> {
         world
             .query::<(&Mesh2D, &Material2D, &Transform)>()
             .iter()
-            .map(|(_, (mesh, material, transform))| (*mesh, *material, *transform))
+            .map(|(_, (mesh, material, transform))| (*mesh, material.clone(), *transform))
             .collect()
     }
 }
+
+impl Viewport {
+    /// Creates a new [`Viewport`] of the given size in physical pixels.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+
+    /// Returns the size of the surface in physical pixels.
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32).max(Vec2::ONE)
+    }
+
+    /// Returns the ratio between the width and height of the surface.
+    pub fn aspect_ratio(&self) -> f32 {
+        let size = self.size();
+        size.x / size.y
+    }
+}
@@ -1,20 +1,29 @@
 use std::sync::Arc;
 
 use anyhow::Context;
+use web_time::Instant;
 
 use wgpu::*;
 use winit::{dpi::PhysicalSize, window::Window};
 
+#[cfg(feature = "inspector")]
+use crate::renderer::overlay::{OverlayFrame, OverlayRenderer};
 use crate::{
     ecs::World,
+    profiler::Profiler,
     renderer::{camera::Camera2D, pipelines::Pipelines, shaders::Shaders, sprite::SpriteRenderer},
     transform::Transform,
 };
 
 pub mod camera;
+#[cfg(feature = "inspector")]
+pub(crate) mod overlay;
 mod pipelines;
 mod shaders;
 pub mod sprite;
+mod texture;
+
+pub(crate) use texture::EmbeddedTextures;
 mod vertex;
 
 /// All state that is required for drawing a full scene and UI.
@@ -31,6 +40,9 @@ pub struct Renderer {
     pipelines: Pipelines,
 
     sprite_renderer: SpriteRenderer,
+
+    #[cfg(feature = "inspector")]
+    overlay_renderer: OverlayRenderer,
 }
 
 impl Renderer {
@@ -77,7 +89,10 @@ impl Renderer {
 
         let pipelines = Pipelines::new(&device, &shaders, &surface_config);
 
-        let sprite_renderer = SpriteRenderer::new(&device, &pipelines);
+        let sprite_renderer = SpriteRenderer::new(&device, &queue, &pipelines);
+
+        #[cfg(feature = "inspector")]
+        let overlay_renderer = OverlayRenderer::new(&device, surface_config.format);
 
         Ok(Self {
             device,
@@ -87,6 +102,8 @@ impl Renderer {
             shaders,
             pipelines,
             sprite_renderer,
+            #[cfg(feature = "inspector")]
+            overlay_renderer,
         })
     }
 
@@ -129,8 +146,13 @@ impl Renderer {
         self.surface.configure(&self.device, &self.surface_config);
     }
 
-    /// Renders the entire scene and all UI.
-    pub fn render(&mut self, world: &mut World) {
+    /// Returns the current size of the target surface.
+    pub fn size(&self) -> PhysicalSize<u32> {
+        PhysicalSize::new(self.surface_config.width, self.surface_config.height)
+    }
+
+    /// Renders the entire scene and all UI, recording the timings of each stage.
+    pub fn render(&mut self, world: &mut World, profiler: &mut Profiler) {
         let output = match self.surface.get_current_texture() {
             Ok(tex) => tex,
 
@@ -147,6 +169,22 @@ impl Renderer {
             .texture
             .create_view(&TextureViewDescriptor::default());
 
+        let mut frames = Vec::new();
+
+        for (_, (camera, transform)) in world.query::<(&Camera2D, &Transform)>().iter() {
+            let extract_start = Instant::now();
+            let sprites = camera.extract_entities(world);
+            profiler.record("Renderer::extract", extract_start, Instant::now());
+
+            // textures are loaded before the render pass begins, so no file I/O happens while
+            // recording it
+            self.sprite_renderer
+                .prepare(&self.device, &self.queue, &self.pipelines, &sprites);
+
+            let transformation = camera.view_projection_matrix(transform, self.aspect_ratio());
+            frames.push((sprites, transformation));
+        }
+
         let mut encoder = self
             .device
             .create_command_encoder(&CommandEncoderDescriptor::default());
@@ -172,14 +210,7 @@ impl Renderer {
                 occlusion_query_set: None,
             });
 
-            for (_, (camera, transform)) in world.query::<(&Camera2D, &Transform)>().iter() {
-                let sprites = camera.extract_entities(&world);
-
-                let projection = camera.projection_matrix(self.aspect_ratio());
-                let view = Camera2D::view_matrix(transform);
-
-                let transformation = view * projection;
-
+            for (sprites, transformation) in frames {
                 self.sprite_renderer.render(
                     &mut pass,
                     &self.queue,
@@ -190,8 +221,35 @@ impl Renderer {
             }
         }
 
-        self.queue.submit([encoder.finish()]);
+        #[cfg(feature = "inspector")]
+        let overlay_commands = self.overlay_renderer.render(
+            &self.device,
+            &self.queue,
+            &mut encoder,
+            &view,
+            [self.surface_config.width, self.surface_config.height],
+        );
+        #[cfg(not(feature = "inspector"))]
+        let overlay_commands = Vec::new();
+
+        let submit_start = Instant::now();
+
+        self.queue
+            .submit(overlay_commands.into_iter().chain([encoder.finish()]));
         output.present();
+
+        profiler.record("Renderer::submit", submit_start, Instant::now());
+    }
+
+    /// Adds encoded images to use in place of the texture files at their paths.
+    pub(crate) fn embed_textures(&mut self, embedded: EmbeddedTextures) {
+        self.sprite_renderer.embed_textures(embedded);
+    }
+
+    /// Queues an overlay frame to be drawn on top of the scene on the next render.
+    #[cfg(feature = "inspector")]
+    pub(crate) fn submit_overlay(&mut self, frame: OverlayFrame) {
+        self.overlay_renderer.submit(frame);
     }
 
     /// Calculates the aspect ratio of the render surface.
@@ -0,0 +1,88 @@
+use egui_wgpu::ScreenDescriptor;
+use wgpu::*;
+
+/// A tessellated frame of the egui overlay, ready to be drawn on top of the scene.
+pub(crate) struct OverlayFrame {
+    /// The tessellated shapes to draw.
+    pub primitives: Vec<egui::ClippedPrimitive>,
+    /// The textures to upload and free this frame.
+    pub textures_delta: egui::TexturesDelta,
+    /// The scale factor the frame was laid out with.
+    pub pixels_per_point: f32,
+}
+
+/// Draws egui overlays on top of the rendered scene.
+pub(crate) struct OverlayRenderer {
+    /// The underlying egui renderer.
+    renderer: egui_wgpu::Renderer,
+    /// The frame to draw on the next render, if any.
+    frame: Option<OverlayFrame>,
+}
+
+impl OverlayRenderer {
+    /// Creates a new [`OverlayRenderer`] targetting the given surface format.
+    pub fn new(device: &Device, format: TextureFormat) -> Self {
+        Self {
+            renderer: egui_wgpu::Renderer::new(device, format, None, 1, false),
+            frame: None,
+        }
+    }
+
+    /// Queues a frame to be drawn on the next render.
+    pub fn submit(&mut self, frame: OverlayFrame) {
+        self.frame = Some(frame);
+    }
+
+    /// Draws the queued frame, if any, onto the given view.
+    ///
+    /// Returns any additional command buffers that must be submitted alongside the encoder.
+    pub fn render(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        view: &TextureView,
+        size_in_pixels: [u32; 2],
+    ) -> Vec<CommandBuffer> {
+        let Some(frame) = self.frame.take() else {
+            return Vec::new();
+        };
+
+        let screen = ScreenDescriptor {
+            size_in_pixels,
+            pixels_per_point: frame.pixels_per_point,
+        };
+
+        for (id, delta) in &frame.textures_delta.set {
+            self.renderer.update_texture(device, queue, *id, delta);
+        }
+
+        let commands =
+            self.renderer
+                .update_buffers(device, queue, encoder, &frame.primitives, &screen);
+
+        {
+            let pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("OverlayRenderer::render_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Load,
+                        store: StoreOp::Store,
+                    },
+                })],
+                ..Default::default()
+            });
+
+            self.renderer
+                .render(&mut pass.forget_lifetime(), &frame.primitives, &screen);
+        }
+
+        for id in &frame.textures_delta.free {
+            self.renderer.free_texture(id);
+        }
+
+        commands
+    }
+}
@@ -12,6 +12,8 @@ pub struct Pipelines {
 
     /// The bind group layout used for holding a camera's transformation matrix.
     pub camera_bind_group_layout: BindGroupLayout,
+    /// The bind group layout used for holding a sprite's texture and sampler.
+    pub texture_bind_group_layout: BindGroupLayout,
 }
 
 impl Pipelines {
@@ -32,10 +34,33 @@ impl Pipelines {
                 }],
             });
 
+        let texture_bind_group_layout =
+            device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: Some("Pipelines::texture_bind_group_layout"),
+                entries: &[
+                    BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Texture {
+                            sample_type: TextureSampleType::Float { filterable: true },
+                            view_dimension: TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+
         let sprite_render_pipeline_layout =
             device.create_pipeline_layout(&PipelineLayoutDescriptor {
                 label: Some("Pipelines::sprite_render_pipeline_layout"),
-                bind_group_layouts: &[&camera_bind_group_layout],
+                bind_group_layouts: &[&camera_bind_group_layout, &texture_bind_group_layout],
                 push_constant_ranges: &[],
             });
 
@@ -54,7 +79,7 @@ impl Pipelines {
                 compilation_options: PipelineCompilationOptions::default(),
                 targets: &[Some(ColorTargetState {
                     format: surface_config.format,
-                    blend: None,
+                    blend: Some(BlendState::ALPHA_BLENDING),
                     write_mask: ColorWrites::ALL,
                 })],
             }),
@@ -75,6 +100,7 @@ impl Pipelines {
         Self {
             sprite_render_pipeline,
             camera_bind_group_layout,
+            texture_bind_group_layout,
         }
     }
 }
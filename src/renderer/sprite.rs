@@ -1,27 +1,29 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
 
-use glam::{Mat4, vec2, vec4};
+use glam::{Mat4, Vec2, vec2, vec4};
+use serde::{Deserialize, Serialize};
 use wgpu::{util::*, wgt::BufferDescriptor, *};
 
 use crate::renderer::{
     pipelines::Pipelines,
+    texture::{EmbeddedTextures, TextureCache},
     vertex::{SpriteInstance, SpriteVertex},
 };
-use crate::transform::Transform;
+use crate::{spatial::Aabb, transform::Transform};
 
 /// A 2D mesh used for rendering.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub struct Mesh2D(pub Shape2D);
 
 /// A 2D primitive that represenets some basic geometry.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub enum Shape2D {
     /// A rectangle centered at the origin with corners at (-1, -1), (1, 1).
     Square,
 }
 
 /// A material used for rendering a 2D sprite
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub enum Material2D {
     /// The entire sprite is shaded a flat color.
     FlatColor {
@@ -32,8 +34,44 @@ pub enum Material2D {
         /// The blue component in the range [0, 1].
         b: f32,
     },
+    /// The sprite is covered by an image, stretched to fit its shape.
+    Textured {
+        /// The path of the image file or embedded texture, loaded on first use.
+        texture: Cow<'static, str>,
+    },
+}
+
+impl Mesh2D {
+    /// Returns the bounding box of the mesh in world space when placed with the given transform.
+    pub fn aabb(&self, transform: &Transform) -> Aabb {
+        let local = self.0.local_aabb();
+        let corners = [
+            local.min,
+            vec2(local.min.x, local.max.y),
+            local.max,
+            vec2(local.max.x, local.min.y),
+        ]
+        .map(|corner| transform.transform_point(corner.extend(0.0)).truncate());
+
+        Aabb::from_points(&corners)
+    }
 }
 
+impl Shape2D {
+    /// Returns the bounding box of the shape in its local space.
+    pub fn local_aabb(self) -> Aabb {
+        match self {
+            Self::Square => Aabb::new(Vec2::NEG_ONE, Vec2::ONE),
+        }
+    }
+}
+
+crate::reflect!(Mesh2D { 0 });
+crate::reflect!(enum Material2D {
+    FlatColor { r, g, b },
+    Textured { texture },
+});
+
 /// The main 2D sprite renderer, responsible for efficiently batching 2D geometry.
 pub(crate) struct SpriteRenderer {
     /// The geometry mesh for squares.
@@ -41,6 +79,11 @@ pub(crate) struct SpriteRenderer {
 
     /// The buffer holding all information for sprites currently being rendered.
     instance_buffer: Buffer,
+    /// The number of sprites the instance buffer can hold, grown as needed.
+    instance_capacity: u64,
+
+    /// All textures used by sprites so far.
+    textures: TextureCache,
 
     /// The bind group holding the camera's transformation matrix.
     camera_bind_group: BindGroup,
@@ -60,17 +103,12 @@ struct GeometryMesh {
 }
 
 impl SpriteRenderer {
-    /// The maximum count of sprites per render batch.
-    pub const MAX_SPRITES_PER_BATCH: u64 = 100;
+    /// The number of sprites the instance buffer can initially hold.
+    pub const INITIAL_INSTANCE_CAPACITY: u64 = 100;
 
     /// Creates a new [`SpriteRenderer`].
-    pub fn new(device: &Device, pipelines: &Pipelines) -> Self {
-        let instance_buffer = device.create_buffer(&BufferDescriptor {
-            label: Some("SpriteRenderer::transform_buffer"),
-            size: Self::MAX_SPRITES_PER_BATCH * size_of::<Transform>() as u64,
-            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+    pub fn new(device: &Device, queue: &Queue, pipelines: &Pipelines) -> Self {
+        let instance_buffer = Self::create_instance_buffer(device, Self::INITIAL_INSTANCE_CAPACITY);
 
         let square_mesh = GeometryMesh::new(
             device,
@@ -78,19 +116,19 @@ impl SpriteRenderer {
             &[
                 SpriteVertex {
                     position: vec2(-1.0, 1.0),
-                    color: [0.5, 0.4, 0.6],
+                    uv: vec2(0.0, 0.0),
                 },
                 SpriteVertex {
                     position: vec2(-1.0, -1.0),
-                    color: [0.5, 0.4, 0.6],
+                    uv: vec2(0.0, 1.0),
                 },
                 SpriteVertex {
                     position: vec2(1.0, -1.0),
-                    color: [0.5, 0.4, 0.6],
+                    uv: vec2(1.0, 1.0),
                 },
                 SpriteVertex {
                     position: vec2(1.0, 1.0),
-                    color: [0.5, 0.4, 0.6],
+                    uv: vec2(1.0, 0.0),
                 },
             ],
             &[0, 1, 2, 2, 3, 0],
@@ -112,22 +150,68 @@ impl SpriteRenderer {
             }],
         });
 
+        let textures = TextureCache::new(device, queue, &pipelines.texture_bind_group_layout);
+
         Self {
             square_mesh,
             instance_buffer,
+            instance_capacity: Self::INITIAL_INSTANCE_CAPACITY,
+            textures,
             camera_bind_group,
             camera_buffer,
         }
     }
 
+    /// Creates a buffer holding the given number of sprite instances.
+    fn create_instance_buffer(device: &Device, capacity: u64) -> Buffer {
+        device.create_buffer(&BufferDescriptor {
+            label: Some("SpriteRenderer::instance_buffer"),
+            size: capacity * size_of::<SpriteInstance>() as u64,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Adds encoded images to use in place of the texture files at their paths.
+    pub fn embed_textures(&mut self, embedded: EmbeddedTextures) {
+        self.textures.embed(embedded);
+    }
+
+    /// Grows the instance buffer to fit the provided sprites and loads every texture they use,
+    /// which must both happen outside of a render pass.
+    pub fn prepare(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        pipelines: &Pipelines,
+        sprites: &[(Mesh2D, Material2D, Transform)],
+    ) {
+        let count = sprites.len() as u64;
+
+        if count > self.instance_capacity {
+            self.instance_capacity = count.next_power_of_two();
+            self.instance_buffer = Self::create_instance_buffer(device, self.instance_capacity);
+        }
+
+        for (_, material, _) in sprites {
+            if let Material2D::Textured { texture } = material {
+                self.textures
+                    .load(device, queue, &pipelines.texture_bind_group_layout, texture);
+            }
+        }
+    }
+
     /// Renders all the provided sprites to the current render pass.
+    ///
+    /// Sprites are drawn back to front by their z translation, so ones further along +z end up on
+    /// top. The sprites must have been passed to [`SpriteRenderer::prepare`] first.
     pub fn render(
         &mut self,
         rpass: &mut RenderPass<'_>,
         queue: &Queue,
         pipelines: &Pipelines,
         camera_transformation: Mat4,
-        sprites: Vec<(Mesh2D, Material2D, Transform)>,
+        mut sprites: Vec<(Mesh2D, Material2D, Transform)>,
     ) {
         rpass.set_pipeline(&pipelines.sprite_render_pipeline);
 
@@ -137,52 +221,77 @@ impl SpriteRenderer {
             bytemuck::bytes_of(&camera_transformation),
         );
 
-        let mut sprites_by_shape = HashMap::<Shape2D, Vec<SpriteInstance>>::new();
-
-        for (Mesh2D(shape), material, transform) in sprites {
-            sprites_by_shape
-                .entry(shape)
-                .or_default()
-                .push(SpriteInstance {
-                    transform: transform.as_model_matrix(),
-                    color: match material {
-                        Material2D::FlatColor { r, g, b } => vec4(r, g, b, 1.0),
-                    },
-                });
-        }
+        // sorting by batch after depth keeps the draw order stable, while still grouping sprites
+        // which share a depth into as few batches as possible
+        sprites.sort_by(
+            |(a_mesh, a_material, a_transform), (b_mesh, b_material, b_transform)| {
+                a_transform
+                    .translation
+                    .z
+                    .total_cmp(&b_transform.translation.z)
+                    .then_with(|| a_mesh.cmp(b_mesh))
+                    .then_with(|| texture_path(a_material).cmp(&texture_path(b_material)))
+            },
+        );
 
-        // shape: (first instance, length)
-        let mut sprite_instance_ranges = HashMap::<Shape2D, (u32, u32)>::new();
+        // (shape, texture): (first instance, length), with `None` being the white texture
+        let mut sprite_instance_ranges = Vec::<((Shape2D, Option<&str>), u32, u32)>::new();
+        let mut instances = Vec::with_capacity(sprites.len());
 
-        let instances =
-            sprites_by_shape
-                .into_iter()
-                .fold(Vec::new(), |mut instances, (shape, sprites)| {
-                    sprite_instance_ranges
-                        .insert(shape, (instances.len() as u32, sprites.len() as u32));
+        for (Mesh2D(shape), material, transform) in &sprites {
+            let batch = (*shape, texture_path(material));
+            let color = match material {
+                Material2D::FlatColor { r, g, b } => vec4(*r, *g, *b, 1.0),
+                Material2D::Textured { .. } => vec4(1.0, 1.0, 1.0, 1.0),
+            };
 
-                    instances.extend(sprites);
-                    instances
-                });
+            match sprite_instance_ranges.last_mut() {
+                Some((last, _, count)) if *last == batch => *count += 1,
+                _ => sprite_instance_ranges.push((batch, instances.len() as u32, 1)),
+            }
+
+            instances.push(SpriteInstance {
+                transform: transform.as_model_matrix(),
+                color,
+            });
+        }
 
         queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
 
         rpass.set_bind_group(0, &self.camera_bind_group, &[]);
         rpass.set_vertex_buffer(1, self.instance_buffer.slice(..));
 
-        for (shape, (first_instance, count)) in sprite_instance_ranges {
+        for ((shape, texture), first_instance, count) in sprite_instance_ranges {
             let mesh = match shape {
                 Shape2D::Square => &self.square_mesh,
             };
 
+            let texture = match texture {
+                Some(path) => self.textures.get(path),
+                None => self.textures.white(),
+            };
+
+            rpass.set_bind_group(1, texture.bind_group(), &[]);
             rpass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
             rpass.set_index_buffer(mesh.index_buffer.slice(..), IndexFormat::Uint16);
 
-            rpass.draw_indexed(0..mesh.index_count, 0, first_instance..count as _);
+            rpass.draw_indexed(
+                0..mesh.index_count,
+                0,
+                first_instance..first_instance + count,
+            );
         }
     }
 }
 
+/// Returns the path of the texture covering a sprite with the given material, if any.
+fn texture_path(material: &Material2D) -> Option<&str> {
+    match material {
+        Material2D::FlatColor { .. } => None,
+        Material2D::Textured { texture } => Some(texture),
+    }
+}
+
 impl GeometryMesh {
     /// Creates and uploads a mesh based on the given vertices and indices.
     fn new(
@@ -0,0 +1,222 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use anyhow::Context;
+use web_time::{Duration, Instant};
+use wgpu::{util::*, *};
+
+/// A texture uploaded to the GPU, along with the bind group used to sample it.
+pub(crate) struct Texture {
+    /// The underlying texture.
+    #[allow(unused)]
+    texture: wgpu::Texture,
+    /// The bind group holding the texture's view and the shared sampler.
+    bind_group: BindGroup,
+}
+
+/// Loads every texture referenced by a sprite on first use and keeps it for later frames.
+pub(crate) struct TextureCache {
+    /// The plain white texture used by untextured sprites and in place of missing textures.
+    white: Texture,
+    /// The sampler shared by all textures.
+    sampler: Sampler,
+    /// All textures loaded so far, keyed by their path.
+    textures: HashMap<Cow<'static, str>, Texture>,
+    /// The paths of all textures which failed to load, along with when they last failed.
+    failed: HashMap<Cow<'static, str>, Instant>,
+    /// The encoded images to use instead of reading from disk, keyed by their path.
+    embedded: EmbeddedTextures,
+}
+
+/// Encoded images bundled into the application, used in place of the file at their path.
+///
+/// Textures can only be loaded this way on the web, as there is no file system to read from.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EmbeddedTextures(pub HashMap<Cow<'static, str>, &'static [u8]>);
+
+impl Texture {
+    /// Creates and uploads a new texture from tightly packed sRGB RGBA pixels.
+    fn from_rgba(
+        device: &Device,
+        queue: &Queue,
+        layout: &BindGroupLayout,
+        sampler: &Sampler,
+        label: &str,
+        size: (u32, u32),
+        pixels: &[u8],
+    ) -> Self {
+        let texture = device.create_texture_with_data(
+            queue,
+            &TextureDescriptor {
+                label: Some(label),
+                size: Extent3d {
+                    width: size.0,
+                    height: size.1,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Rgba8UnormSrgb,
+                usage: TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+            TextureDataOrder::LayerMajor,
+            pixels,
+        );
+
+        let view = texture.create_view(&TextureViewDescriptor::default());
+
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some(&format!("{label}::bind_group")),
+            layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(sampler),
+                },
+            ],
+        });
+
+        Self {
+            texture,
+            bind_group,
+        }
+    }
+
+    /// Returns the bind group holding the texture and its sampler.
+    pub fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
+
+impl TextureCache {
+    /// How long to wait before trying to load a texture again after it failed to load.
+    pub const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+    /// Creates a new, empty [`TextureCache`] holding only the white texture.
+    pub fn new(device: &Device, queue: &Queue, layout: &BindGroupLayout) -> Self {
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("TextureCache::sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let white = Texture::from_rgba(
+            device,
+            queue,
+            layout,
+            &sampler,
+            "TextureCache::white",
+            (1, 1),
+            &[255; 4],
+        );
+
+        Self {
+            white,
+            sampler,
+            textures: HashMap::new(),
+            failed: HashMap::new(),
+            embedded: EmbeddedTextures::default(),
+        }
+    }
+
+    /// Adds encoded images to use instead of reading the files at their paths.
+    pub fn embed(&mut self, embedded: EmbeddedTextures) {
+        for path in embedded.0.keys() {
+            self.textures.remove(path);
+            self.failed.remove(path);
+        }
+
+        self.embedded.0.extend(embedded.0);
+    }
+
+    /// Returns the plain white texture.
+    pub fn white(&self) -> &Texture {
+        &self.white
+    }
+
+    /// Loads the texture at the given path if it hasn't been loaded yet.
+    ///
+    /// This may read from disk, so it must be called before recording a render pass rather than
+    /// during it. Failed textures resolve to the white texture and are retried every
+    /// [`RETRY_DELAY`](Self::RETRY_DELAY), with the failure only logged the first time.
+    pub fn load(&mut self, device: &Device, queue: &Queue, layout: &BindGroupLayout, path: &str) {
+        if self.textures.contains_key(path)
+            || self
+                .failed
+                .get(path)
+                .is_some_and(|failed| failed.elapsed() < Self::RETRY_DELAY)
+        {
+            return;
+        }
+
+        let image = match self.embedded.0.get(path) {
+            Some(bytes) => decode_image(path, bytes),
+            None => load_image(path),
+        };
+
+        match image {
+            Ok(image) => {
+                let texture = Texture::from_rgba(
+                    device,
+                    queue,
+                    layout,
+                    &self.sampler,
+                    path,
+                    image.dimensions(),
+                    &image,
+                );
+
+                self.failed.remove(path);
+                self.textures.insert(Cow::Owned(path.to_owned()), texture);
+            }
+
+            Err(e) => {
+                if self
+                    .failed
+                    .insert(Cow::Owned(path.to_owned()), Instant::now())
+                    .is_none()
+                {
+                    log::error!("failed to load texture: {e:#}");
+                }
+            }
+        }
+    }
+
+    /// Returns the texture at the given path, or the white texture if it isn't loaded.
+    pub fn get(&self, path: &str) -> &Texture {
+        self.textures.get(path).unwrap_or(&self.white)
+    }
+}
+
+/// Decodes an encoded image into RGBA pixels.
+fn decode_image(path: &str, bytes: &[u8]) -> anyhow::Result<image::RgbaImage> {
+    let image =
+        image::load_from_memory(bytes).with_context(|| format!("while decoding image {path}"))?;
+    Ok(image.into_rgba8())
+}
+
+/// Reads and decodes the image at the given path into RGBA pixels.
+#[cfg(not(target_family = "wasm"))]
+fn load_image(path: &str) -> anyhow::Result<image::RgbaImage> {
+    let image = image::open(path).with_context(|| format!("while loading image {path}"))?;
+    Ok(image.into_rgba8())
+}
+
+/// Reads and decodes the image at the given path into RGBA pixels.
+///
+/// There is no file system on the web, so only embedded textures can be loaded there.
+#[cfg(target_family = "wasm")]
+fn load_image(path: &str) -> anyhow::Result<image::RgbaImage> {
+    anyhow::bail!(
+        "while loading image {path}: there is no file system on the web, embed the image through \
+        `ApplicationBuilder::with_embedded_texture` instead"
+    )
+}
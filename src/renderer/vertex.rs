@@ -8,8 +8,8 @@ use wgpu::{VertexBufferLayout, VertexStepMode, vertex_attr_array};
 pub struct SpriteVertex {
     /// The 2D world space coordinates of the vertex.
     pub position: Vec2,
-    /// The flat color of the vertex.
-    pub color: [f32; 3],
+    /// The texture coordinates of the vertex, with (0, 0) at the top left of the texture.
+    pub uv: Vec2,
 }
 
 /// The instance data per 2D sprite.
@@ -18,7 +18,7 @@ pub struct SpriteVertex {
 pub struct SpriteInstance {
     /// The model matrix transformation of the sprite.
     pub transform: Mat4,
-    /// The flat color of the sprite.
+    /// The color the sprite's texture is multiplied with.
     pub color: Vec4,
 }
 
@@ -30,6 +30,7 @@ impl SpriteVertex {
         attributes: &{
             vertex_attr_array![
                 0 => Float32x2,
+                1 => Float32x2,
             ]
         },
     };
@@ -42,11 +43,11 @@ impl SpriteInstance {
         step_mode: VertexStepMode::Instance,
         attributes: &{
             vertex_attr_array![
-                1 => Float32x4,
                 2 => Float32x4,
                 3 => Float32x4,
                 4 => Float32x4,
                 5 => Float32x4,
+                6 => Float32x4,
             ]
         },
     };
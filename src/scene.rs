@@ -0,0 +1,196 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+
+use anyhow::{Context, anyhow};
+use hecs::{Entity, EntityBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{ecs::World, reflect::TypeRegistry};
+
+/// A serialized subset of a [`World`], stored as human readable JSON.
+///
+/// Scenes are saved and spawned through the [`TypeRegistry`], so only registered component types
+/// are included.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Scene {
+    /// All entities in the scene along with their components.
+    pub entities: Vec<SceneEntity>,
+}
+
+/// A single serialized entity inside of a [`Scene`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneEntity {
+    /// The id of the entity at the time it was saved, used for remapping entity references.
+    pub id: u64,
+    /// The serialized components of the entity, keyed by their registered name.
+    pub components: BTreeMap<String, Value>,
+}
+
+/// A mapping from the entities stored in a [`Scene`] to the entities they were spawned as.
+#[derive(Debug, Clone, Default)]
+pub struct EntityMap(HashMap<Entity, Entity>);
+
+/// A component which holds references to other entities, which must be remapped when loading
+/// a [`Scene`].
+pub trait MapEntities {
+    /// Replaces all held entity references with their newly spawned counterparts, see
+    /// [`EntityMap::map`].
+    fn map_entities(&mut self, map: &EntityMap);
+}
+
+impl Scene {
+    /// Parses a [`Scene`] from its JSON representation.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        serde_json::from_str(json).context("while parsing scene")
+    }
+
+    /// Converts this [`Scene`] into its pretty printed JSON representation.
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        serde_json::to_string_pretty(self).context("while serializing scene")
+    }
+
+    /// Reads and parses a [`Scene`] from the file at the given path.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("while reading scene file {}", path.display()))?;
+
+        Self::from_json(&json)
+    }
+
+    /// Writes this [`Scene`] to the file at the given path.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json()?)
+            .with_context(|| format!("while writing scene file {}", path.display()))
+    }
+}
+
+impl EntityMap {
+    /// Returns the newly spawned entity for an entity stored in the scene.
+    pub fn get(&self, entity: Entity) -> Option<Entity> {
+        self.0.get(&entity).copied()
+    }
+
+    /// Maps an entity reference, returning [`Entity::DANGLING`] if it wasn't part of the scene.
+    ///
+    /// Unmapped references are never kept as-is, as they would point at whatever unrelated entity
+    /// happens to have the same id in the world the scene is spawned into.
+    pub fn map(&self, entity: Entity) -> Entity {
+        self.get(entity).unwrap_or(Entity::DANGLING)
+    }
+
+    /// Returns an iterator over all (scene entity, spawned entity) pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        self.0.iter().map(|(from, to)| (*from, *to))
+    }
+}
+
+impl TypeRegistry {
+    /// Saves the registered components of the given entities into a [`Scene`].
+    pub fn save_scene(
+        &self,
+        world: &World,
+        entities: impl IntoIterator<Item = Entity>,
+    ) -> anyhow::Result<Scene> {
+        let mut scene = Scene::default();
+
+        for entity in entities {
+            let mut components = BTreeMap::new();
+
+            for registration in self.iter() {
+                if let Some(value) = registration.serialize(world, entity) {
+                    let name = registration.name();
+                    let value = value.with_context(|| format!("while serializing {name}"))?;
+                    components.insert(name.to_string(), value);
+                }
+            }
+
+            if !components.is_empty() {
+                scene.entities.push(SceneEntity {
+                    id: entity.to_bits().get(),
+                    components,
+                });
+            }
+        }
+
+        Ok(scene)
+    }
+
+    /// Saves every entity in the world with at least one registered component into a [`Scene`].
+    pub fn save_world(&self, world: &World) -> anyhow::Result<Scene> {
+        let entities = world
+            .iter()
+            .map(|entity| entity.entity())
+            .collect::<Vec<_>>();
+        self.save_scene(world, entities)
+    }
+
+    /// Spawns all entities of a [`Scene`] into the world, alongside any existing entities.
+    ///
+    /// All components are deserialized before anything is spawned, so nothing is spawned if the
+    /// scene contains an invalid or unregistered component. Returns the mapping from the scene's
+    /// entities to their newly spawned counterparts.
+    pub fn spawn_scene(&self, world: &mut World, scene: &Scene) -> anyhow::Result<EntityMap> {
+        let mut builders = Vec::with_capacity(scene.entities.len());
+
+        for scene_entity in &scene.entities {
+            let mut builder = EntityBuilder::new();
+
+            for (name, value) in &scene_entity.components {
+                self.get(name)
+                    .ok_or_else(|| anyhow!("unregistered scene component `{name}`"))?
+                    .insert(&mut builder, value.clone())
+                    .with_context(|| format!("while loading {name}"))?;
+            }
+
+            builders.push(builder);
+        }
+
+        let spawned = builders
+            .iter_mut()
+            .map(|builder| world.spawn(builder.build()))
+            .collect::<Vec<_>>();
+
+        let map = EntityMap(
+            scene
+                .entities
+                .iter()
+                .zip(&spawned)
+                .filter_map(|(scene_entity, entity)| {
+                    Some((Entity::from_bits(scene_entity.id)?, *entity))
+                })
+                .collect(),
+        );
+
+        for (scene_entity, entity) in scene.entities.iter().zip(&spawned) {
+            for name in scene_entity.components.keys() {
+                if let Some(registration) = self.get(name) {
+                    registration.map_entities(world, *entity, &map);
+                }
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+/// Serde helpers for entity references inside of components, for use with `#[serde(with)]`.
+pub mod entity_serde {
+    use hecs::Entity;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    /// Serializes an entity as its raw bits.
+    pub fn serialize<S: Serializer>(entity: &Entity, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(entity.to_bits().get())
+    }
+
+    /// Deserializes an entity from its raw bits.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Entity, D::Error> {
+        let bits = u64::deserialize(deserializer)?;
+        Entity::from_bits(bits).ok_or_else(|| D::Error::custom("invalid entity bits"))
+    }
+}
@@ -0,0 +1,611 @@
+use std::collections::{HashMap, HashSet};
+
+use glam::{I64Vec2, IVec2, Vec2};
+use hecs::Entity;
+
+use crate::{
+    ecs::{Resources, World},
+    renderer::sprite::Mesh2D,
+    transform::Transform,
+};
+
+/// An axis-aligned bounding box in 2D world space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    /// The corner with the smallest coordinates.
+    pub min: Vec2,
+    /// The corner with the largest coordinates.
+    pub max: Vec2,
+}
+
+/// A single entity hit by a ray cast through the [`SpatialIndex`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// The entity which was hit.
+    pub entity: Entity,
+    /// The distance along the ray to the first point of the entity's bounding box.
+    pub distance: f32,
+    /// The first point of the entity's bounding box hit by the ray.
+    pub point: Vec2,
+}
+
+/// Keeps the world-space bounding boxes of all renderable entities in a uniform grid, allowing
+/// fast point, rectangle, circle and ray queries without scanning the whole world.
+///
+/// The index is updated from every entity with a [`Mesh2D`] and [`Transform`] before all update
+/// systems each frame, only moving entities between cells when they cross a cell boundary.
+#[derive(Debug, Clone)]
+pub struct SpatialIndex {
+    /// The width and height of a single grid cell in world units.
+    cell_size: f32,
+    /// The entities overlapping each non-empty cell.
+    cells: HashMap<IVec2, Vec<Entity>>,
+    /// The bounding box and covered cells of every indexed entity.
+    entries: HashMap<Entity, SpatialEntry>,
+    /// The entities overlapping too many cells to be stored in them, checked by every query.
+    oversized: Vec<Entity>,
+    /// Incremented on every sync, used to find entities which are no longer renderable.
+    generation: u64,
+}
+
+/// An entity stored in the [`SpatialIndex`].
+#[derive(Debug, Clone, Copy)]
+struct SpatialEntry {
+    /// The bounding box of the entity in world space.
+    aabb: Aabb,
+    /// The cell with the smallest coordinates the entity overlaps.
+    min_cell: IVec2,
+    /// The cell with the largest coordinates the entity overlaps.
+    max_cell: IVec2,
+    /// The generation of the sync the entity was last seen in.
+    generation: u64,
+}
+
+impl Aabb {
+    /// Creates a new [`Aabb`] from its minimum and maximum corners.
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+
+    /// Creates a new [`Aabb`] from its center and half of its size.
+    pub fn from_center_half_size(center: Vec2, half_size: Vec2) -> Self {
+        Self::new(center - half_size, center + half_size)
+    }
+
+    /// Creates the smallest [`Aabb`] containing all given points, which must not be empty.
+    pub fn from_points(points: &[Vec2]) -> Self {
+        points.iter().fold(
+            Self::new(Vec2::INFINITY, Vec2::NEG_INFINITY),
+            |aabb, point| Self::new(aabb.min.min(*point), aabb.max.max(*point)),
+        )
+    }
+
+    /// Returns the center of the box.
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Returns half of the width and height of the box.
+    pub fn half_size(&self) -> Vec2 {
+        self.size() * 0.5
+    }
+
+    /// Returns the width and height of the box.
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    /// Returns the smallest box containing both this and the other box.
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// Returns the point inside the box closest to the given point.
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        point.clamp(self.min, self.max)
+    }
+
+    /// Returns if the point lies inside the box, including its edges.
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    /// Returns if this box overlaps the other box, including touching edges.
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && self.max.cmpge(other.min).all()
+    }
+
+    /// Returns if this box overlaps the circle with the given center and radius.
+    pub fn intersects_circle(&self, center: Vec2, radius: f32) -> bool {
+        self.closest_point(center).distance_squared(center) <= radius * radius
+    }
+
+    /// Returns the distance along the ray at which it first enters the box, or zero if it starts
+    /// inside, if it does so within the given distance.
+    ///
+    /// The direction must be normalized for the distance to be in world units.
+    pub fn ray_intersection(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+    ) -> Option<f32> {
+        let inverse = direction.recip();
+        let a = (self.min - origin) * inverse;
+        let b = (self.max - origin) * inverse;
+
+        let enter = a.min(b).max_element().max(0.0);
+        let exit = a.max(b).min_element();
+
+        (enter <= exit && enter <= max_distance).then_some(enter)
+    }
+}
+
+impl SpatialIndex {
+    /// The default width and height of a single grid cell in world units.
+    pub const DEFAULT_CELL_SIZE: f32 = 4.0;
+
+    /// The largest cell coordinate in either direction, beyond which positions are clamped.
+    const MAX_CELL: f32 = (1 << 20) as f32;
+    /// The most cells an entity may overlap before it is kept in the oversized list instead.
+    const MAX_CELLS_PER_ENTITY: i64 = 256;
+
+    /// Creates a new, empty [`SpatialIndex`] with the given cell size in world units, which
+    /// should be about the size of a typical entity.
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            entries: HashMap::new(),
+            oversized: Vec::new(),
+            generation: 0,
+        }
+    }
+
+    /// Returns the width and height of a single grid cell in world units.
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Sets the width and height of a single grid cell, rebuilding the grid.
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        let entries = std::mem::take(&mut self.entries);
+
+        self.cell_size = cell_size;
+        self.cells.clear();
+        self.oversized.clear();
+
+        for (entity, entry) in entries {
+            self.insert(entity, entry.aabb);
+        }
+    }
+
+    /// Returns the number of indexed entities.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns if no entities are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the world-space bounding box of the given entity, if it is indexed.
+    pub fn aabb(&self, entity: Entity) -> Option<Aabb> {
+        self.entries.get(&entity).map(|entry| entry.aabb)
+    }
+
+    /// Returns an iterator over all indexed entities and their bounding boxes.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Aabb)> {
+        self.entries
+            .iter()
+            .map(|(entity, entry)| (*entity, entry.aabb))
+    }
+
+    /// Returns all entities whose bounding box contains the given point, e.g. for picking.
+    pub fn query_point(&self, point: Vec2) -> Vec<Entity> {
+        self.cells
+            .get(&self.cell(point))
+            .into_iter()
+            .flatten()
+            .chain(&self.oversized)
+            .copied()
+            .filter(|entity| self.entries[entity].aabb.contains(point))
+            .collect()
+    }
+
+    /// Returns all entities whose bounding box overlaps the given rectangle, e.g. for culling.
+    pub fn query_rect(&self, rect: Aabb) -> Vec<Entity> {
+        self.query(rect, |aabb| aabb.intersects(&rect))
+    }
+
+    /// Returns all entities whose bounding box overlaps the circle with the given center and
+    /// radius.
+    pub fn query_circle(&self, center: Vec2, radius: f32) -> Vec<Entity> {
+        let bounds = Aabb::from_center_half_size(center, Vec2::splat(radius));
+        self.query(bounds, |aabb| aabb.intersects_circle(center, radius))
+    }
+
+    /// Returns all entities whose bounding box is hit by the ray within the given distance, which
+    /// may be infinite, sorted from nearest to furthest.
+    pub fn raycast(&self, origin: Vec2, direction: Vec2, max_distance: f32) -> Vec<RayHit> {
+        let Some(direction) = direction.try_normalize() else {
+            return Vec::new();
+        };
+
+        let mut hits = Vec::new();
+        let mut visited = HashSet::new();
+
+        let mut hit = |entity: Entity| {
+            if !visited.insert(entity) {
+                return;
+            }
+
+            if let Some(distance) =
+                self.entries[&entity]
+                    .aabb
+                    .ray_intersection(origin, direction, max_distance)
+            {
+                hits.push(RayHit {
+                    entity,
+                    distance,
+                    point: origin + direction * distance,
+                });
+            }
+        };
+
+        self.oversized.iter().copied().for_each(&mut hit);
+
+        let mut cell = self.cell(origin);
+        let last_cell = self.cell(origin + direction * max_distance);
+        let steps = (last_cell.as_i64vec2() - cell.as_i64vec2())
+            .abs()
+            .element_sum();
+
+        // testing every entity is cheaper than walking a long ray through mostly empty cells
+        if !max_distance.is_finite() || steps > self.entries.len() as i64 {
+            self.entries.keys().copied().for_each(&mut hit);
+            hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+            return hits;
+        }
+
+        // walk all cells along the ray, see "A Fast Voxel Traversal Algorithm" by Amanatides & Woo
+        let step = IVec2::new(
+            if direction.x < 0.0 { -1 } else { 1 },
+            if direction.y < 0.0 { -1 } else { 1 },
+        );
+
+        let boundary_distance = |cell: i32, step: i32, origin: f32, direction: f32| {
+            if direction == 0.0 {
+                return f32::INFINITY;
+            }
+
+            let boundary = (cell + step.max(0)) as f32 * self.cell_size;
+            (boundary - origin) / direction
+        };
+
+        let mut next_boundary = Vec2::new(
+            boundary_distance(cell.x, step.x, origin.x, direction.x),
+            boundary_distance(cell.y, step.y, origin.y, direction.y),
+        );
+        let boundary_step = self.cell_size / direction.abs();
+
+        loop {
+            for entity in self.cells.get(&cell).into_iter().flatten() {
+                hit(*entity);
+            }
+
+            if cell == last_cell || next_boundary.min_element() > max_distance {
+                break;
+            }
+
+            if next_boundary.x < next_boundary.y {
+                cell.x += step.x;
+                next_boundary.x += boundary_step.x;
+            } else {
+                cell.y += step.y;
+                next_boundary.y += boundary_step.y;
+            }
+        }
+
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    /// Returns the nearest entity whose bounding box is hit by the ray within the given, finite
+    /// distance.
+    pub fn raycast_first(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+    ) -> Option<RayHit> {
+        self.raycast(origin, direction, max_distance)
+            .first()
+            .copied()
+    }
+
+    /// Returns all entities in the cells covered by the bounds which pass the filter, without
+    /// duplicates.
+    fn query(&self, bounds: Aabb, filter: impl Fn(&Aabb) -> bool) -> Vec<Entity> {
+        let (min_cell, max_cell) = self.cell_range(&bounds);
+        let cell_count = cell_count(min_cell, max_cell);
+
+        // scanning every entity is cheaper than visiting mostly empty cells for large bounds
+        if cell_count > self.entries.len() as i64 {
+            return self
+                .entries
+                .iter()
+                .filter(|(_, entry)| filter(&entry.aabb))
+                .map(|(entity, _)| *entity)
+                .collect();
+        }
+
+        let mut visited = HashSet::new();
+
+        cells(min_cell, max_cell)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .chain(&self.oversized)
+            .copied()
+            .filter(|entity| visited.insert(*entity) && filter(&self.entries[entity].aabb))
+            .collect()
+    }
+
+    /// Returns the cell containing the given point, clamped to the extent of the grid.
+    fn cell(&self, point: Vec2) -> IVec2 {
+        (point / self.cell_size)
+            .floor()
+            .clamp(Vec2::splat(-Self::MAX_CELL), Vec2::splat(Self::MAX_CELL))
+            .as_ivec2()
+    }
+
+    /// Returns the range of cells overlapped by the given box.
+    fn cell_range(&self, aabb: &Aabb) -> (IVec2, IVec2) {
+        (self.cell(aabb.min), self.cell(aabb.max))
+    }
+
+    /// Inserts or moves an entity with the given bounding box, only touching the grid if the
+    /// cells it overlaps changed.
+    fn insert(&mut self, entity: Entity, aabb: Aabb) {
+        let (min_cell, max_cell) = self.cell_range(&aabb);
+        let generation = self.generation;
+
+        if let Some(entry) = self.entries.get_mut(&entity)
+            && entry.min_cell == min_cell
+            && entry.max_cell == max_cell
+        {
+            entry.aabb = aabb;
+            entry.generation = generation;
+            return;
+        }
+
+        self.remove(entity);
+
+        if cell_count(min_cell, max_cell) > Self::MAX_CELLS_PER_ENTITY {
+            self.oversized.push(entity);
+        } else {
+            for cell in cells(min_cell, max_cell) {
+                self.cells.entry(cell).or_default().push(entity);
+            }
+        }
+
+        self.entries.insert(
+            entity,
+            SpatialEntry {
+                aabb,
+                min_cell,
+                max_cell,
+                generation,
+            },
+        );
+    }
+
+    /// Removes an entity from the index and all cells it overlaps.
+    fn remove(&mut self, entity: Entity) {
+        let Some(entry) = self.entries.remove(&entity) else {
+            return;
+        };
+
+        if cell_count(entry.min_cell, entry.max_cell) > Self::MAX_CELLS_PER_ENTITY {
+            self.oversized.retain(|other| *other != entity);
+            return;
+        }
+
+        for cell in cells(entry.min_cell, entry.max_cell) {
+            if let Some(entities) = self.cells.get_mut(&cell) {
+                entities.retain(|other| *other != entity);
+
+                if entities.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+
+    /// Updates the bounding boxes of all renderable entities, removing the ones which were
+    /// despawned or are no longer renderable.
+    pub(crate) fn sync(world: &mut World, resources: &mut Resources) {
+        let index = resources.get_mut::<SpatialIndex>();
+        index.generation += 1;
+
+        for (entity, (mesh, transform)) in world.query::<(&Mesh2D, &Transform)>().iter() {
+            index.insert(entity, mesh.aabb(transform));
+        }
+
+        let stale = index
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.generation != index.generation)
+            .map(|(entity, _)| *entity)
+            .collect::<Vec<_>>();
+
+        for entity in stale {
+            index.remove(entity);
+        }
+    }
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CELL_SIZE)
+    }
+}
+
+/// Returns the number of cells between the two corners, inclusive.
+fn cell_count(min: IVec2, max: IVec2) -> i64 {
+    let size = max.as_i64vec2() - min.as_i64vec2() + I64Vec2::ONE;
+    size.x.max(0) * size.y.max(0)
+}
+
+/// Returns an iterator over all cells between the two corners, inclusive.
+fn cells(min: IVec2, max: IVec2) -> impl Iterator<Item = IVec2> {
+    (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an index with one entity per given box, returning the entities in order.
+    fn index_with(boxes: &[Aabb]) -> (SpatialIndex, Vec<Entity>) {
+        let mut world = hecs::World::new();
+        let mut index = SpatialIndex::default();
+
+        let entities = boxes
+            .iter()
+            .map(|aabb| {
+                let entity = world.spawn(());
+                index.insert(entity, *aabb);
+                entity
+            })
+            .collect();
+
+        (index, entities)
+    }
+
+    fn unit_box(center: Vec2) -> Aabb {
+        Aabb::from_center_half_size(center, Vec2::splat(0.5))
+    }
+
+    #[test]
+    fn ray_intersection() {
+        let aabb = unit_box(Vec2::new(5.0, 0.0));
+
+        assert_eq!(aabb.ray_intersection(Vec2::ZERO, Vec2::X, 100.0), Some(4.5));
+        assert_eq!(aabb.ray_intersection(Vec2::ZERO, Vec2::NEG_X, 100.0), None);
+        assert_eq!(aabb.ray_intersection(Vec2::ZERO, Vec2::Y, 100.0), None);
+        assert_eq!(aabb.ray_intersection(Vec2::ZERO, Vec2::X, 4.0), None);
+        assert_eq!(
+            aabb.ray_intersection(Vec2::new(5.0, 0.0), Vec2::Y, 100.0),
+            Some(0.0)
+        );
+
+        let diagonal = Vec2::ONE.normalize();
+        let distance = unit_box(Vec2::splat(3.0))
+            .ray_intersection(Vec2::ZERO, diagonal, f32::INFINITY)
+            .unwrap();
+        assert!((distance - 2.5 * 2.0f32.sqrt()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn raycast_sorts_hits_across_cells() {
+        let (index, entities) = index_with(&[
+            unit_box(Vec2::new(21.0, 0.5)),
+            unit_box(Vec2::new(3.0, 0.5)),
+            unit_box(Vec2::new(11.0, 0.5)),
+            unit_box(Vec2::new(11.0, 9.0)),
+        ]);
+
+        let hits = index.raycast(Vec2::new(0.0, 0.5), Vec2::X, 30.0);
+        let hit_entities = hits.iter().map(|hit| hit.entity).collect::<Vec<_>>();
+
+        assert_eq!(hit_entities, [entities[1], entities[2], entities[0]]);
+        assert_eq!(hits[0].distance, 2.5);
+        assert_eq!(hits[0].point, Vec2::new(2.5, 0.5));
+
+        let first = index.raycast_first(Vec2::new(30.0, 0.5), Vec2::NEG_X, 30.0);
+        assert_eq!(first.map(|hit| hit.entity), Some(entities[0]));
+
+        let short = index.raycast(Vec2::new(0.0, 0.5), Vec2::X, 10.0);
+        assert_eq!(short.len(), 1);
+    }
+
+    #[test]
+    fn raycast_walks_diagonally_and_backwards() {
+        let (index, entities) = index_with(&[
+            unit_box(Vec2::new(-10.0, -10.0)),
+            unit_box(Vec2::new(-5.0, -5.0)),
+            unit_box(Vec2::new(-5.0, 5.0)),
+        ]);
+
+        let hits = index.raycast(Vec2::ZERO, Vec2::NEG_ONE, 20.0);
+        let hit_entities = hits.iter().map(|hit| hit.entity).collect::<Vec<_>>();
+
+        assert_eq!(hit_entities, [entities[1], entities[0]]);
+    }
+
+    #[test]
+    fn raycast_with_infinite_distance_terminates() {
+        let (index, entities) = index_with(&[unit_box(Vec2::new(1e6, 0.0))]);
+
+        let hits = index.raycast(Vec2::ZERO, Vec2::X, f32::INFINITY);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].entity, entities[0]);
+
+        assert!(index.raycast(Vec2::ZERO, Vec2::Y, f32::INFINITY).is_empty());
+        assert!(index.raycast(Vec2::ZERO, Vec2::ZERO, 10.0).is_empty());
+    }
+
+    #[test]
+    fn query_circle() {
+        let (index, entities) = index_with(&[
+            unit_box(Vec2::new(2.0, 0.0)),
+            unit_box(Vec2::new(2.0, 2.0)),
+            unit_box(Vec2::new(10.0, 0.0)),
+        ]);
+
+        let mut found = index.query_circle(Vec2::ZERO, 2.0);
+        found.sort();
+
+        // the second box overlaps the circle's bounds, but not the circle itself
+        assert_eq!(found, [entities[0]]);
+
+        let mut found = index.query_circle(Vec2::ZERO, 2.2);
+        found.sort();
+
+        assert_eq!(found, [entities[0], entities[1]]);
+    }
+
+    #[test]
+    fn huge_queries_do_not_overflow() {
+        let (index, entities) = index_with(&[
+            unit_box(Vec2::ZERO),
+            Aabb::new(Vec2::splat(-1e12), Vec2::splat(1e12)),
+        ]);
+
+        let mut found = index.query_rect(Aabb::new(Vec2::splat(-1e12), Vec2::splat(1e12)));
+        found.sort();
+        assert_eq!(found, entities);
+
+        let mut found = index.query_point(Vec2::new(1e9, -1e9));
+        found.sort();
+        assert_eq!(found, [entities[1]]);
+
+        let hits = index.raycast(Vec2::splat(-1e11), Vec2::ONE, 1e12);
+        assert_eq!(hits.len(), 2);
+    }
+
+    #[test]
+    fn moving_and_removing_entities() {
+        let (mut index, entities) = index_with(&[unit_box(Vec2::ZERO)]);
+
+        index.insert(entities[0], unit_box(Vec2::new(50.0, 50.0)));
+        assert!(index.query_point(Vec2::ZERO).is_empty());
+        assert_eq!(index.query_point(Vec2::new(50.0, 50.0)), entities);
+
+        index.remove(entities[0]);
+        assert!(index.is_empty());
+        assert!(index.cells.is_empty());
+    }
+}
@@ -1,4 +1,11 @@
-use web_time::{Duration, Instant};
+use hecs::Entity;
+use serde::{Deserialize, Serialize};
+use web_time::Duration;
+
+use crate::{
+    clock::{Clock, SystemClock},
+    ecs::{Resources, World},
+};
 
 /// Manages all state related to frame timings.
 pub struct FrameTimer {
@@ -6,54 +13,465 @@ pub struct FrameTimer {
     frame_count: u32,
     /// The smoothed out fps measure.
     fps: f32,
-    /// How long the previous frame took to complete in seconds.
+    /// The total number of frames since the timer was created.
+    frame: u64,
+
+    /// How long the previous frame took to complete in real time.
+    real_delta: Duration,
+    /// How long the previous frame took to complete in real seconds.
+    real_delta_time: f32,
+    /// The total real time passed since the timer was created in seconds.
+    real_elapsed: f64,
+
+    /// How long the previous frame took in virtual seconds, after scaling and pausing.
     delta_time: f32,
+    /// The total virtual time passed since the timer was created in seconds.
+    elapsed: f64,
+    /// The speed virtual time runs at relative to real time.
+    time_scale: f32,
+    /// Whether virtual time is currently paused.
+    paused: bool,
+
+    /// The source of time the timer is ticked with.
+    clock: Box<dyn Clock>,
+    /// The time of the last frame, as read from the clock.
+    last_frame: Duration,
+    /// The time of the last full second, as read from the clock.
+    last_second: Duration,
+}
+
+/// Whether a [`Timer`] runs once or restarts every time it finishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TimerMode {
+    /// The timer stops once it finishes.
+    Once,
+    /// The timer restarts every time it finishes.
+    Repeating,
+}
+
+/// A countdown timer, ticked automatically every frame when attached to an entity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Timer {
+    /// How long the timer runs for in seconds.
+    duration: f32,
+    /// How long the timer has been running for in seconds, since it last finished.
+    elapsed: f32,
+    /// Whether the timer runs once or repeats.
+    mode: TimerMode,
+    /// Whether the timer is currently paused.
+    paused: bool,
+    /// Whether a timer running once has finished, which only happens while ticking it.
+    finished: bool,
+    /// How many times the timer finished during the last tick.
+    times_finished: u32,
+}
+
+/// A stopwatch counting up the time passed, ticked automatically every frame when attached to
+/// an entity.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stopwatch {
+    /// How long the stopwatch has been running for in seconds.
+    elapsed: f32,
+    /// Whether the stopwatch is currently paused.
+    paused: bool,
+}
+
+/// Despawns the entity it is attached to once its timer runs out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lifetime(pub Timer);
+
+crate::reflect!(Timer {
+    duration,
+    elapsed,
+    mode,
+    paused,
+    finished,
+    times_finished
+});
+crate::reflect!(Stopwatch { elapsed, paused });
+crate::reflect!(Lifetime { 0 });
+
+/// A queue of closures to run on the world after a delay.
+#[derive(Default)]
+pub struct DelayedCommands {
+    /// The queued commands along with the timer until they run.
+    pending: Vec<(Timer, DelayedCommand)>,
+}
+
+/// A closure to run on the world after a delay.
+type DelayedCommand = Box<dyn FnOnce(&mut World, &mut Resources) + 'static>;
+
+impl Timer {
+    /// Creates a new [`Timer`] running for the given number of seconds.
+    pub fn new(duration: f32, mode: TimerMode) -> Self {
+        Self {
+            duration,
+            elapsed: 0.0,
+            mode,
+            paused: false,
+            finished: false,
+            times_finished: 0,
+        }
+    }
+
+    /// Creates a new [`Timer`] which finishes once after the given number of seconds.
+    pub fn once(duration: f32) -> Self {
+        Self::new(duration, TimerMode::Once)
+    }
+
+    /// Creates a new [`Timer`] which finishes every time the given number of seconds pass.
+    pub fn repeating(duration: f32) -> Self {
+        Self::new(duration, TimerMode::Repeating)
+    }
+
+    /// Advances the timer by the given delta time in seconds.
+    ///
+    /// Timers with a duration of zero finish on their first tick.
+    pub fn tick(&mut self, dt: f32) -> &Self {
+        self.times_finished = 0;
+
+        if self.paused || self.finished {
+            return self;
+        }
+
+        self.elapsed += dt;
+
+        if self.elapsed < self.duration {
+            return self;
+        }
+
+        match self.mode {
+            TimerMode::Once => {
+                self.elapsed = self.duration;
+                self.finished = true;
+                self.times_finished = 1;
+            }
+            TimerMode::Repeating if self.duration > 0.0 => {
+                self.times_finished = (self.elapsed / self.duration) as u32;
+                self.elapsed %= self.duration;
+            }
+            TimerMode::Repeating => {
+                self.elapsed = 0.0;
+                self.times_finished = 1;
+            }
+        }
+
+        self
+    }
+
+    /// Returns if the timer has finished; repeating timers are only finished on the tick they
+    /// wrapped around.
+    pub fn finished(&self) -> bool {
+        match self.mode {
+            TimerMode::Once => self.finished,
+            TimerMode::Repeating => self.just_finished(),
+        }
+    }
+
+    /// Returns if the timer finished during the last tick.
+    pub fn just_finished(&self) -> bool {
+        self.times_finished > 0
+    }
+
+    /// Returns how many times the timer finished during the last tick.
+    pub fn times_finished(&self) -> u32 {
+        self.times_finished
+    }
+
+    /// Returns how long the timer has been running for in seconds, since it last finished.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Returns how many seconds are left until the timer finishes.
+    pub fn remaining(&self) -> f32 {
+        (self.duration - self.elapsed).max(0.0)
+    }
+
+    /// Returns the fraction of the timer's duration that has elapsed, in the range [0, 1].
+    pub fn fraction(&self) -> f32 {
+        if self.duration > 0.0 {
+            (self.elapsed / self.duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns how long the timer runs for in seconds.
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Sets how long the timer runs for in seconds.
+    pub fn set_duration(&mut self, duration: f32) {
+        self.duration = duration;
+    }
+
+    /// Returns whether the timer runs once or repeats.
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    /// Restarts the timer from zero.
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+        self.finished = false;
+        self.times_finished = 0;
+    }
+
+    /// Pauses the timer, so that ticking it has no effect.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes the timer after being paused.
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns if the timer is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
 
-    /// The time of the last frame.
-    last_frame: Instant,
-    /// The time of the last full second.
-    last_second: Instant,
+impl Stopwatch {
+    /// Creates a new, running [`Stopwatch`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances the stopwatch by the given delta time in seconds.
+    pub fn tick(&mut self, dt: f32) -> &Self {
+        if !self.paused {
+            self.elapsed += dt;
+        }
+
+        self
+    }
+
+    /// Returns how long the stopwatch has been running for in seconds.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Restarts the stopwatch from zero.
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+    }
+
+    /// Pauses the stopwatch, so that ticking it has no effect.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes the stopwatch after being paused.
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns if the stopwatch is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
+
+impl Lifetime {
+    /// Creates a new [`Lifetime`] lasting for the given number of seconds.
+    pub fn new(seconds: f32) -> Self {
+        Self(Timer::once(seconds))
+    }
+}
+
+impl DelayedCommands {
+    /// Queues a closure to run on the world once the given number of seconds have passed.
+    pub fn after(
+        &mut self,
+        seconds: f32,
+        command: impl FnOnce(&mut World, &mut Resources) + 'static,
+    ) {
+        self.pending.push((Timer::once(seconds), Box::new(command)));
+    }
+
+    /// Returns the number of commands still waiting to run.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns if no commands are waiting to run.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Drops all commands waiting to run.
+    ///
+    /// Used before unloading a hot reloadable library, as commands queued by its systems point
+    /// into its code.
+    #[cfg(all(feature = "hot-reload", not(target_family = "wasm")))]
+    pub(crate) fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// Ticks all [`Timer`], [`Stopwatch`] and [`Lifetime`] components along with all
+/// [`DelayedCommands`], despawning expired entities and running due commands.
+pub(crate) fn tick_timers(world: &mut World, resources: &mut Resources) {
+    let dt = resources.get::<FrameTimer>().dt();
+
+    for (_, timer) in world.query_mut::<&mut Timer>() {
+        timer.tick(dt);
+    }
+
+    for (_, stopwatch) in world.query_mut::<&mut Stopwatch>() {
+        stopwatch.tick(dt);
+    }
+
+    let expired = world
+        .query_mut::<&mut Lifetime>()
+        .into_iter()
+        .filter_map(|(entity, lifetime)| lifetime.0.tick(dt).finished().then_some(entity))
+        .collect::<Vec<Entity>>();
+
+    for entity in expired {
+        let _ = world.despawn(entity);
+    }
+
+    let pending = std::mem::take(&mut resources.get_mut::<DelayedCommands>().pending);
+    let (due, waiting) = pending
+        .into_iter()
+        .map(|(mut timer, command)| {
+            timer.tick(dt);
+            (timer, command)
+        })
+        .partition::<Vec<_>, _>(|(timer, _)| timer.finished());
+
+    resources
+        .get_mut::<DelayedCommands>()
+        .pending
+        .splice(0..0, waiting);
+
+    for (_, command) in due {
+        command(world, resources);
+    }
 }
 
 impl FrameTimer {
-    /// Creates a new [`FrameTimer`]
+    /// Creates a new [`FrameTimer`] reading the system clock.
     pub fn new() -> Self {
+        Self::with_clock(SystemClock::new())
+    }
+
+    /// Creates a new [`FrameTimer`] reading the given clock.
+    pub fn with_clock(clock: impl Clock) -> Self {
+        let mut clock = Box::new(clock);
+        let now = clock.now();
+
         Self {
             frame_count: 0,
             fps: 0.0,
+            frame: 0,
+            real_delta: Duration::ZERO,
+            real_delta_time: 0.0,
+            real_elapsed: 0.0,
             delta_time: 0.0,
-            last_frame: Instant::now(),
-            last_second: Instant::now(),
+            elapsed: 0.0,
+            time_scale: 1.0,
+            paused: false,
+            clock,
+            last_frame: now,
+            last_second: now,
         }
     }
 
     /// Updates the timer. Should be called once per frame.
     pub fn tick(&mut self) {
         self.frame_count += 1;
+        self.frame += 1;
+
+        let now = self.clock.now();
 
-        let elapsed_frame_time = self.last_frame.elapsed();
-        self.delta_time = elapsed_frame_time.as_secs_f32();
-        self.last_frame = Instant::now();
+        let elapsed_frame_time = now.saturating_sub(self.last_frame);
+        self.real_delta = elapsed_frame_time;
+        self.real_delta_time = elapsed_frame_time.as_secs_f32();
+        self.real_elapsed += elapsed_frame_time.as_secs_f64();
+        self.last_frame = now;
 
-        let elapsed_second_time = self.last_second.elapsed();
+        self.delta_time = if self.paused {
+            0.0
+        } else {
+            self.real_delta_time * self.time_scale
+        };
+        self.elapsed += self.delta_time as f64;
+
+        let elapsed_second_time = now.saturating_sub(self.last_second);
         if elapsed_second_time > Duration::from_secs(1) {
             self.fps = self.frame_count as f32 / elapsed_second_time.as_secs_f32();
-            self.last_second = Instant::now();
+            self.last_second = now;
             self.frame_count = 0;
-
-            log::info!("running at {:.4} fps", self.fps);
         }
     }
 
-    /// Returns the current delta time.
+    /// Returns the current virtual delta time, which is scaled and zero while paused.
+    ///
+    /// Gameplay systems should use this, while UI should use [`FrameTimer::real_dt`].
     pub fn dt(&self) -> f32 {
         self.delta_time
     }
 
+    /// Returns the current real delta time, unaffected by scaling or pausing.
+    pub fn real_dt(&self) -> f32 {
+        self.real_delta_time
+    }
+
+    /// Returns the exact real duration of the previous frame.
+    pub fn real_delta(&self) -> Duration {
+        self.real_delta
+    }
+
+    /// Returns the total virtual time passed in seconds.
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Returns the total real time passed in seconds.
+    pub fn real_elapsed(&self) -> f64 {
+        self.real_elapsed
+    }
+
+    /// Returns the total number of frames ticked so far.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
     /// Returns the current smoothed fps.
     pub fn fps(&self) -> f32 {
         self.fps
     }
+
+    /// Returns the speed virtual time runs at relative to real time.
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Sets the speed virtual time runs at relative to real time, e.g. `0.5` for slow motion.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.max(0.0);
+    }
+
+    /// Pauses virtual time, so that [`FrameTimer::dt`] returns zero.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes virtual time after being paused.
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns if virtual time is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
 }
 
 impl Default for FrameTimer {
@@ -61,3 +479,120 @@ impl Default for FrameTimer {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::clock::ManualClock;
+
+    /// Creates the resources needed by [`tick_timers`], along with the clock driving them.
+    fn resources() -> (Resources, ManualClock) {
+        let clock = ManualClock::new();
+
+        let mut resources = Resources::new();
+        resources.insert(FrameTimer::with_clock(clock.clone()));
+        resources.insert(DelayedCommands::default());
+
+        (resources, clock)
+    }
+
+    /// Advances the clock by the given number of seconds and ticks all timers.
+    fn step(world: &mut World, resources: &mut Resources, clock: &ManualClock, seconds: f32) {
+        clock.advance(Duration::from_secs_f32(seconds));
+        resources.get_mut::<FrameTimer>().tick();
+        tick_timers(world, resources);
+    }
+
+    #[test]
+    fn once_timers_finish_a_single_time() {
+        let mut timer = Timer::once(1.0);
+
+        assert!(!timer.tick(0.6).finished());
+        assert!(timer.tick(0.6).just_finished());
+        assert_eq!(timer.remaining(), 0.0);
+
+        assert!(timer.tick(0.6).finished());
+        assert!(!timer.just_finished());
+
+        timer.reset();
+        assert!(!timer.finished());
+    }
+
+    #[test]
+    fn zero_length_timers_finish_on_the_first_tick() {
+        let mut timer = Timer::once(0.0);
+        assert!(!timer.finished());
+
+        assert!(timer.tick(0.0).just_finished());
+        assert!(!timer.tick(0.0).just_finished());
+        assert!(timer.finished());
+    }
+
+    #[test]
+    fn repeating_timers_wrap_multiple_times_per_tick() {
+        let mut timer = Timer::repeating(0.25);
+
+        assert_eq!(timer.tick(0.6).times_finished(), 2);
+        assert!((timer.elapsed() - 0.1).abs() < 1e-5);
+
+        assert_eq!(timer.tick(0.1).times_finished(), 0);
+        assert!(!timer.finished());
+
+        assert_eq!(timer.tick(0.05).times_finished(), 1);
+        assert!(timer.finished());
+    }
+
+    #[test]
+    fn paused_timers_ignore_ticks() {
+        let mut timer = Timer::once(1.0);
+        timer.pause();
+
+        assert!(!timer.tick(2.0).finished());
+        assert_eq!(timer.elapsed(), 0.0);
+
+        timer.unpause();
+        assert!(timer.tick(2.0).just_finished());
+    }
+
+    #[test]
+    fn lifetimes_despawn_their_entity() {
+        let (mut resources, clock) = resources();
+        let mut world = World::new();
+        let short = world.spawn((Lifetime::new(0.5),));
+        let long = world.spawn((Lifetime::new(2.0),));
+
+        step(&mut world, &mut resources, &clock, 0.3);
+        assert!(world.contains(short));
+
+        step(&mut world, &mut resources, &clock, 0.3);
+        assert!(!world.contains(short));
+        assert!(world.contains(long));
+    }
+
+    #[test]
+    fn delayed_commands_run_in_due_order() {
+        let (mut resources, clock) = resources();
+        let mut world = World::new();
+        let ran = Rc::new(RefCell::new(Vec::new()));
+
+        for (seconds, name) in [(1.0, "late"), (0.5, "first"), (0.5, "second"), (0.0, "now")] {
+            let ran = Rc::clone(&ran);
+            resources
+                .get_mut::<DelayedCommands>()
+                .after(seconds, move |_, _| ran.borrow_mut().push(name));
+        }
+
+        step(&mut world, &mut resources, &clock, 0.0);
+        assert_eq!(*ran.borrow(), ["now"]);
+
+        step(&mut world, &mut resources, &clock, 0.5);
+        assert_eq!(*ran.borrow(), ["now", "first", "second"]);
+        assert_eq!(resources.get::<DelayedCommands>().len(), 1);
+
+        step(&mut world, &mut resources, &clock, 0.5);
+        assert_eq!(*ran.borrow(), ["now", "first", "second", "late"]);
+        assert!(resources.get::<DelayedCommands>().is_empty());
+    }
+}
@@ -1,7 +1,10 @@
-use glam::{Mat4, Quat, Vec3};
+use std::ops::Mul;
+
+use glam::{Mat3, Mat4, Quat, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
 
 /// The transformation of an object in world space.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Transform {
     /// The translation of the object relative to its center.
     pub translation: Vec3,
@@ -12,6 +15,13 @@ pub struct Transform {
 }
 
 impl Transform {
+    /// The identity transformation, which leaves everything unchanged.
+    pub const IDENTITY: Self = Self {
+        translation: Vec3::ZERO,
+        scale: Vec3::ONE,
+        rotation: Quat::IDENTITY,
+    };
+
     /// Creates a new [`Transform`] from a given translation.
     pub fn with_translation(translation: Vec3) -> Self {
         Self {
@@ -19,6 +29,7 @@ impl Transform {
             ..Default::default()
         }
     }
+
     /// Creates a new [`Transform`] from a given scale.
     pub fn with_scale(scale: Vec3) -> Self {
         Self {
@@ -35,18 +46,164 @@ impl Transform {
         }
     }
 
+    /// Creates a new [`Transform`] rotated by the given angle in radians around the z axis.
+    pub fn with_angle_2d(angle: f32) -> Self {
+        Self::with_rotation(Quat::from_rotation_z(angle))
+    }
+
+    /// Creates a new [`Transform`] from an affine matrix, discarding any shear or projection.
+    pub fn from_matrix(matrix: Mat4) -> Self {
+        let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
+
+        Self {
+            translation,
+            scale,
+            rotation,
+        }
+    }
+
     /// Converts this [`Transform`] into a model matrix.
     pub fn as_model_matrix(&self) -> Mat4 {
         Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
     }
+
+    /// Combines this transform with a child transform relative to it, such that the result
+    /// applies the child first.
+    ///
+    /// Non-uniform scales combined with rotations introduce shear, which can't be represented and
+    /// is discarded.
+    pub fn mul_transform(&self, child: Transform) -> Self {
+        Self {
+            translation: self.transform_point(child.translation),
+            scale: self.scale * child.scale,
+            rotation: self.rotation * child.rotation,
+        }
+    }
+
+    /// Returns the transform undoing this one, which is exact for uniform scales.
+    pub fn inverse(&self) -> Self {
+        let rotation = self.rotation.inverse();
+        let scale = self.scale.recip();
+
+        Self {
+            translation: -(scale * (rotation * self.translation)),
+            scale,
+            rotation,
+        }
+    }
+
+    /// Transforms a point from local into world space, applying scale, rotation and translation.
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.translation + self.rotation * (self.scale * point)
+    }
+
+    /// Returns the local negative z direction in world space, which cameras look along.
+    pub fn forward(&self) -> Vec3 {
+        self.rotation * Vec3::NEG_Z
+    }
+
+    /// Returns the local x direction in world space.
+    pub fn right(&self) -> Vec3 {
+        self.rotation * Vec3::X
+    }
+
+    /// Returns the local y direction in world space.
+    pub fn up(&self) -> Vec3 {
+        self.rotation * Vec3::Y
+    }
+
+    /// Rotates this transform so that [`Transform::forward`] points at the target and
+    /// [`Transform::up`] points as closely as possible along the given up direction.
+    ///
+    /// Leaves the rotation unchanged if the target lies on the translation or along the up
+    /// direction.
+    pub fn look_at(&mut self, target: Vec3, up: Vec3) {
+        let Some(back) = (self.translation - target).try_normalize() else {
+            return;
+        };
+        let Some(right) = up.cross(back).try_normalize() else {
+            return;
+        };
+
+        self.rotation = Quat::from_mat3(&Mat3::from_cols(right, back.cross(right), back));
+    }
+
+    /// Rotates this transform around a point in world space, changing both its translation and
+    /// rotation.
+    pub fn rotate_around(&mut self, point: Vec3, rotation: Quat) {
+        self.translation = point + rotation * (self.translation - point);
+        self.rotation = rotation * self.rotation;
+    }
+
+    /// Returns the counter-clockwise rotation around the z axis in radians, as used in 2D.
+    pub fn angle_2d(&self) -> f32 {
+        let right = self.right();
+        right.y.atan2(right.x)
+    }
+
+    /// Sets the rotation to the given counter-clockwise angle around the z axis in radians.
+    pub fn set_angle_2d(&mut self, angle: f32) {
+        self.rotation = Quat::from_rotation_z(angle);
+    }
+
+    /// Rotates this transform counter-clockwise around the z axis by the given angle in radians.
+    pub fn rotate_2d(&mut self, angle: f32) {
+        self.rotation = Quat::from_rotation_z(angle) * self.rotation;
+    }
+
+    /// Rotates this transform around the z axis so that [`Transform::right`] points at the
+    /// target, as 2D sprites usually face right.
+    pub fn look_at_2d(&mut self, target: Vec2) {
+        let direction = target - self.translation.truncate();
+
+        if direction != Vec2::ZERO {
+            self.set_angle_2d(direction.to_angle());
+        }
+    }
+
+    /// Linearly interpolates between this transform and another, using a normalized linear
+    /// interpolation for the rotation.
+    pub fn lerp(&self, other: Transform, t: f32) -> Self {
+        Self {
+            translation: self.translation.lerp(other.translation, t),
+            scale: self.scale.lerp(other.scale, t),
+            rotation: self.rotation.lerp(other.rotation, t),
+        }
+    }
+
+    /// Interpolates between this transform and another, using a spherical interpolation for the
+    /// rotation to keep a constant angular velocity.
+    pub fn slerp(&self, other: Transform, t: f32) -> Self {
+        Self {
+            translation: self.translation.lerp(other.translation, t),
+            scale: self.scale.lerp(other.scale, t),
+            rotation: self.rotation.slerp(other.rotation, t),
+        }
+    }
+}
+
+impl Mul for Transform {
+    type Output = Self;
+
+    fn mul(self, child: Self) -> Self {
+        self.mul_transform(child)
+    }
+}
+
+impl From<Mat4> for Transform {
+    fn from(matrix: Mat4) -> Self {
+        Self::from_matrix(matrix)
+    }
 }
 
+crate::reflect!(Transform {
+    translation,
+    scale,
+    rotation
+});
+
 impl Default for Transform {
     fn default() -> Self {
-        Self {
-            translation: Vec3::ZERO,
-            scale: Vec3::ONE,
-            rotation: Quat::IDENTITY,
-        }
+        Self::IDENTITY
     }
 }
@@ -0,0 +1,137 @@
+use ferret_engine::prelude::{
+    Application, Axis2D, Events, FixedClock, FrameTimer, Headless, InputEvent, InputMap,
+    InputState, Name, NameIndex, Resources, Tags, TextInput, Transform, World,
+};
+use glam::Vec3;
+use winit::{
+    event::ElementState,
+    keyboard::{Key, KeyCode, PhysicalKey},
+};
+
+/// The text typed so far, collected from [`TextInput`] events.
+#[derive(Default)]
+struct Typed(String);
+
+fn spawn_player(world: &mut World, _: &mut Resources) {
+    world.spawn((Name::new("player"), Transform::default()));
+}
+
+/// Moves the player along the `move` axis, like the sandbox's player input system.
+fn handle_player_input(world: &mut World, resources: &mut Resources) {
+    let dt = resources.get::<FrameTimer>().dt();
+    let input = resources.get::<InputState>();
+    let input_map = resources.get::<InputMap>();
+
+    let Some(player) = resources.get::<NameIndex>().get("player") else {
+        return;
+    };
+    let Ok(mut transform) = world.get::<&mut Transform>(player) else {
+        return;
+    };
+
+    transform.translation += input_map.axis(input, "move").extend(0.0) * 5.0 * dt;
+}
+
+fn collect_text(_: &mut World, resources: &mut Resources) {
+    let text = resources
+        .get::<Events<TextInput>>()
+        .iter()
+        .map(|TextInput(text)| text.clone())
+        .collect::<String>();
+
+    resources.get_mut::<Typed>().0.push_str(&text);
+}
+
+fn key(code: KeyCode, text: &str, state: ElementState) -> InputEvent {
+    InputEvent::Key {
+        physical: PhysicalKey::Code(code),
+        logical: Key::Character(text.into()),
+        state,
+        repeat: false,
+        text: (state == ElementState::Pressed).then(|| text.to_owned()),
+    }
+}
+
+fn app() -> Headless {
+    let mut app = Application::builder()
+        .with_clock(FixedClock::from_fps(60.0))
+        .with_input_map(InputMap::new().with_axis("move", Axis2D::wasd()))
+        .add_startup_system(spawn_player)
+        .add_update_system(handle_player_input)
+        .add_update_system(collect_text)
+        .build_headless();
+
+    app.resources_mut().insert(Typed::default());
+    app
+}
+
+fn player_translation(app: &Headless) -> Vec3 {
+    let player = app.resources().get::<NameIndex>().get("player").unwrap();
+    app.world().get::<&Transform>(player).unwrap().translation
+}
+
+#[test]
+fn injected_input_drives_player_systems() {
+    let mut app = app();
+    app.update();
+
+    assert_eq!(player_translation(&app), Vec3::ZERO);
+
+    app.inject(key(KeyCode::KeyD, "d", ElementState::Pressed));
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let moved = player_translation(&app);
+    assert!(moved.x > 0.0);
+    assert_eq!(moved.y, 0.0);
+
+    app.inject(key(KeyCode::KeyD, "d", ElementState::Released));
+    app.update();
+    let stopped = player_translation(&app);
+
+    app.update();
+    assert_eq!(player_translation(&app), stopped);
+}
+
+#[test]
+fn injected_keys_send_text_input() {
+    let mut app = app();
+
+    for (code, text) in [(KeyCode::KeyH, "h"), (KeyCode::KeyI, "i")] {
+        app.inject(key(code, text, ElementState::Pressed));
+        app.inject(key(code, text, ElementState::Released));
+        app.update();
+    }
+
+    assert_eq!(app.resources().get::<Typed>().0, "hi");
+}
+
+#[test]
+fn name_index_follows_name_and_tag_changes() {
+    let mut app = app();
+    let enemy = app
+        .world_mut()
+        .spawn((Name::new("enemy"), Tags::new(["hostile", "flying"])));
+    let bird = app.world_mut().spawn((Tags::new(["flying"]),));
+    app.update();
+
+    let index = app.resources().get::<NameIndex>();
+    assert_eq!(index.get("enemy"), Some(enemy));
+    assert_eq!(
+        index.tagged_all(&["flying", "hostile"]).collect::<Vec<_>>(),
+        [enemy]
+    );
+    assert_eq!(index.tagged_any(&["flying", "hostile"]).count(), 2);
+
+    let world = app.world_mut();
+    world.remove_one::<Name>(enemy).unwrap();
+    world.insert_one(enemy, Name::new("boss")).unwrap();
+    world.despawn(bird).unwrap();
+    app.update();
+
+    let index = app.resources().get::<NameIndex>();
+    assert_eq!(index.get("enemy"), None);
+    assert_eq!(index.get("boss"), Some(enemy));
+    assert_eq!(index.tagged("flying").collect::<Vec<_>>(), [enemy]);
+}